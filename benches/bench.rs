@@ -1,8 +1,11 @@
+use carball::actor_handlers::{TimeSeriesCarColumns, TimeSeriesCarData};
 use carball::analysis::CarballAnalyzer;
+use carball::frame_parser::FrameParser;
 use carball::outputs::DataFramesOutput;
 use carball::outputs::MetadataOutput;
-use carball::CarballParser;
+use carball::{read_file, CarballParser};
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 pub fn bench_parse(c: &mut Criterion) {
@@ -18,6 +21,62 @@ pub fn bench_parse(c: &mut Criterion) {
     });
 }
 
+pub fn bench_stream_frames(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Parse");
+    let file_path = PathBuf::from("assets\\replays\\ranked-3s.replay");
+    let replay = read_file(&file_path).expect("failed to read replay");
+
+    group.sample_size(20);
+    group.bench_function("bench-stream-frames", |b| {
+        b.iter(|| {
+            let frame_parser =
+                FrameParser::new(black_box(&replay)).expect("failed to create frame parser");
+            for frame_snapshot in frame_parser
+                .frames(&replay, false)
+                .expect("failed to create frame iterator")
+            {
+                black_box(frame_snapshot.expect("failed to process frame"));
+            }
+        })
+    });
+}
+
+/// Compares storing a car's time series in per-frame HashMaps (as before) against preallocated columns,
+/// inserting every frame then reading them back in frame order as DataFrame generation does.
+pub fn bench_time_series_storage(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Time Series Storage");
+    // Roughly a 5 minute replay at 30 frames per second.
+    let frame_count = 10_000;
+    let car_data = TimeSeriesCarData {
+        pos_x: Some(1.0),
+        pos_y: Some(2.0),
+        pos_z: Some(3.0),
+        ..Default::default()
+    };
+
+    group.bench_function("bench-hash-map-storage", |b| {
+        b.iter(|| {
+            let mut time_series: HashMap<usize, TimeSeriesCarData> = HashMap::new();
+            for frame_number in 0..frame_count {
+                time_series.insert(frame_number, black_box(car_data));
+            }
+            let pos_x: Vec<Option<f32>> = (0..frame_count)
+                .map(|frame_number| time_series.get(&frame_number).and_then(|data| data.pos_x))
+                .collect();
+            black_box(pos_x)
+        })
+    });
+    group.bench_function("bench-column-storage", |b| {
+        b.iter(|| {
+            let mut time_series = TimeSeriesCarColumns::new(frame_count);
+            for frame_number in 0..frame_count {
+                time_series.insert(frame_number, black_box(car_data));
+            }
+            black_box(time_series.pos_x.clone())
+        })
+    });
+}
+
 pub fn bench_generate_metadata_output(c: &mut Criterion) {
     let mut group = c.benchmark_group("Generate Output");
     let file_path = PathBuf::from("assets\\replays\\ranked-3s.replay");
@@ -28,7 +87,7 @@ pub fn bench_generate_metadata_output(c: &mut Criterion) {
         b.iter(|| {
             MetadataOutput::generate_from(
                 black_box(&carball_parser.replay),
-                black_box(&carball_parser.frame_parser),
+                black_box(&carball_parser.parsed_replay),
             )
         })
     });
@@ -41,7 +100,7 @@ pub fn bench_generate_data_frame_output(c: &mut Criterion) {
     let carball_parser = CarballParser::parse_file(file_path, false).expect("failed to parse");
     group.sample_size(100);
     group.bench_function("bench-generate-data-frame", |b| {
-        b.iter(|| DataFramesOutput::generate_from(black_box(&carball_parser.frame_parser)))
+        b.iter(|| DataFramesOutput::generate_from(black_box(&carball_parser.parsed_replay)))
     });
 }
 
@@ -50,15 +109,16 @@ pub fn bench_analyze(c: &mut Criterion) {
     let file_path = PathBuf::from("assets\\replays\\ranked-3s.replay");
     let carball_parser = CarballParser::parse_file(file_path, false).expect("failed to parse");
     let metadata =
-        MetadataOutput::generate_from(&carball_parser.replay, &carball_parser.frame_parser);
-    let data_frames = DataFramesOutput::generate_from(&carball_parser.frame_parser)
+        MetadataOutput::generate_from(&carball_parser.replay, &carball_parser.parsed_replay)
+            .expect("failed to generate metadata");
+    let data_frames = DataFramesOutput::generate_from(&carball_parser.parsed_replay)
         .expect("failed to generate DataFrames");
     group.sample_size(20);
 
     group.bench_function("bench-analyze", |b| {
         b.iter(|| {
             CarballAnalyzer::analyze(
-                black_box(&carball_parser),
+                black_box(&carball_parser.parsed_replay),
                 black_box(&metadata),
                 black_box(&data_frames),
             )
@@ -69,6 +129,8 @@ pub fn bench_analyze(c: &mut Criterion) {
 criterion_group!(
     benches,
     bench_parse,
+    bench_stream_frames,
+    bench_time_series_storage,
     bench_generate_metadata_output,
     bench_generate_data_frame_output,
     bench_analyze,
@@ -0,0 +1,156 @@
+use boxcars::{Attribute, ObjectId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+macro_rules! attribute_keys {
+    ($($key:ident => $object_name:expr,)*) => {
+        /// Attributes read by carball's actor handlers.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum AttributeKey {
+            $($key,)*
+        }
+
+        impl AttributeKey {
+            pub const ALL: &'static [AttributeKey] = &[$(AttributeKey::$key,)*];
+
+            /// The attribute's property name in the replay's objects.
+            pub fn object_name(self) -> &'static str {
+                match self {
+                    $(AttributeKey::$key => $object_name,)*
+                }
+            }
+        }
+    };
+}
+
+attribute_keys! {
+    RigidBodyState => "TAGame.RBActor_TA:ReplicatedRBState",
+    HitTeamNum => "TAGame.Ball_TA:HitTeamNum",
+    PlayerName => "Engine.PlayerReplicationInfo:PlayerName",
+    UniqueId => "Engine.PlayerReplicationInfo:UniqueId",
+    IsBot => "Engine.PlayerReplicationInfo:bBot",
+    IsSpectator => "Engine.PlayerReplicationInfo:bIsSpectator",
+    PlayerTeam => "Engine.PlayerReplicationInfo:Team",
+    Ping => "Engine.PlayerReplicationInfo:Ping",
+    MatchScore => "TAGame.PRI_TA:MatchScore",
+    MatchGoals => "TAGame.PRI_TA:MatchGoals",
+    MatchAssists => "TAGame.PRI_TA:MatchAssists",
+    MatchSaves => "TAGame.PRI_TA:MatchSaves",
+    MatchShots => "TAGame.PRI_TA:MatchShots",
+    ClientLoadout => "TAGame.PRI_TA:ClientLoadout",
+    ClientLoadouts => "TAGame.PRI_TA:ClientLoadouts",
+    ClientLoadoutsOnline => "TAGame.PRI_TA:ClientLoadoutsOnline",
+    PartyLeader => "TAGame.PRI_TA:PartyLeader",
+    PawnPlayerReplicationInfo => "Engine.Pawn:PlayerReplicationInfo",
+    ReplicatedDemolish => "TAGame.Car_TA:ReplicatedDemolish",
+    Throttle => "TAGame.Vehicle_TA:ReplicatedThrottle",
+    Steer => "TAGame.Vehicle_TA:ReplicatedSteer",
+    Handbrake => "TAGame.Vehicle_TA:bReplicatedHandbrake",
+    CarComponentVehicle => "TAGame.CarComponent_TA:Vehicle",
+    CarComponentActive => "TAGame.CarComponent_TA:ReplicatedActive",
+    BoostAmount => "TAGame.CarComponent_Boost_TA:ReplicatedBoostAmount",
+    DodgeTorque => "TAGame.CarComponent_Dodge_TA:DodgeTorque",
+    SpecialPickupTargeted => "TAGame.SpecialPickup_Targeted_TA:Targeted",
+    SecondsRemaining => "TAGame.GameEvent_Soccar_TA:SecondsRemaining",
+    GameStateTimeRemaining => "TAGame.GameEvent_TA:ReplicatedGameStateTimeRemaining",
+    OverTime => "TAGame.GameEvent_Soccar_TA:bOverTime",
+    BallHasBeenHit => "TAGame.GameEvent_Soccar_TA:bBallHasBeenHit",
+    MatchEnded => "TAGame.GameEvent_Soccar_TA:bMatchEnded",
+    TeamScore => "Engine.TeamInfo:Score",
+    TeamName => "Engine.TeamInfo:TeamName",
+    CustomTeamName => "TAGame.Team_TA:CustomTeamName",
+    ClubId => "TAGame.Team_TA:ClubID",
+    ClubColors => "TAGame.Team_TA:ClubColors",
+    CameraSettingsPlayer => "TAGame.CameraSettingsActor_TA:PRI",
+    CameraProfileSettings => "TAGame.CameraSettingsActor_TA:ProfileSettings",
+    UsingSecondaryCamera => "TAGame.CameraSettingsActor_TA:bUsingSecondaryCamera",
+    ReplicatedPickupData => "TAGame.VehiclePickup_TA:ReplicatedPickupData",
+    NewReplicatedPickupData => "TAGame.VehiclePickup_TA:NewReplicatedPickupData",
+}
+
+/// Object ids of the replay's property names, resolved once per replay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayObjectIds {
+    /// Indexed by `AttributeKey`.
+    attribute_object_ids: Vec<Option<ObjectId>>,
+    object_ids_by_name: HashMap<String, ObjectId>,
+}
+
+impl ReplayObjectIds {
+    pub fn new(replay_objects: &[String]) -> Self {
+        let mut object_ids_by_name = HashMap::with_capacity(replay_objects.len());
+        for (index, object_name) in replay_objects.iter().enumerate() {
+            object_ids_by_name
+                .entry(object_name.clone())
+                .or_insert(ObjectId(index as i32));
+        }
+        let attribute_object_ids = AttributeKey::ALL
+            .iter()
+            .map(|key| object_ids_by_name.get(key.object_name()).copied())
+            .collect();
+        Self {
+            attribute_object_ids,
+            object_ids_by_name,
+        }
+    }
+
+    pub fn get(&self, key: AttributeKey) -> Option<ObjectId> {
+        self.attribute_object_ids[key as usize]
+    }
+
+    pub fn get_by_name(&self, object_name: &str) -> Option<ObjectId> {
+        self.object_ids_by_name.get(object_name).copied()
+    }
+}
+
+/// An actor's latest attributes, keyed by the attribute's object id.
+#[derive(Debug, Clone)]
+pub struct ActorAttributes {
+    object_ids: Arc<ReplayObjectIds>,
+    attributes: HashMap<ObjectId, Attribute>,
+}
+
+impl ActorAttributes {
+    pub fn new(object_ids: Arc<ReplayObjectIds>) -> Self {
+        Self {
+            object_ids,
+            attributes: HashMap::new(),
+        }
+    }
+
+    pub fn insert(&mut self, object_id: ObjectId, attribute: Attribute) {
+        self.attributes.insert(object_id, attribute);
+    }
+
+    pub fn get(&self, key: AttributeKey) -> Option<&Attribute> {
+        self.attributes.get(&self.object_ids.get(key)?)
+    }
+
+    pub fn remove(&mut self, key: AttributeKey) -> Option<Attribute> {
+        self.attributes.remove(&self.object_ids.get(key)?)
+    }
+
+    /// Gets an attribute not covered by `AttributeKey` (e.g. for custom handlers).
+    pub fn get_by_name(&self, object_name: &str) -> Option<&Attribute> {
+        self.attributes
+            .get(&self.object_ids.get_by_name(object_name)?)
+    }
+
+    pub fn object_ids(&self) -> &ReplayObjectIds {
+        &self.object_ids
+    }
+
+    pub fn get_by_object_id(&self, object_id: ObjectId) -> Option<&Attribute> {
+        self.attributes.get(&object_id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ObjectId, &Attribute)> {
+        self.attributes.iter()
+    }
+}
+
+impl PartialEq for ActorAttributes {
+    fn eq(&self, other: &ActorAttributes) -> bool {
+        self.attributes == other.attributes
+    }
+}
@@ -1,4 +1,4 @@
-use crate::actor_handlers::{ActorHandler, RigidBodyData};
+use crate::actor_handlers::{ActorHandler, ActorHandlerError, AttributeKey, RigidBodyData};
 use crate::frame_parser::{Actor, FrameParser};
 use boxcars::Attribute;
 
@@ -12,43 +12,52 @@ impl<'a> ActorHandler<'a> for BallHandler<'a> {
         Self { frame_parser }
     }
 
-    fn update(&mut self, actor: &Actor, frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         // Add time-series ball data
-        let mut ball_data = self.frame_parser.time_series_ball_data.borrow_mut();
-        let _ball_data = TimeSeriesBallData::from(actor, self.frame_parser.replay_version);
-        ball_data.insert(frame_number, _ball_data);
+        let mut frame_snapshot = self.frame_parser.current_frame_snapshot.borrow_mut();
+        let _ball_data = TimeSeriesBallData::from(actor, self.frame_parser.replay_version)?;
+        frame_snapshot.ball_data = Some(_ball_data);
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct TimeSeriesBallData {
-    pub is_sleeping: Option<bool>,
-    pub hit_team_num: Option<u8>,
-    pub pos_x: Option<f32>,
-    pub pos_y: Option<f32>,
-    pub pos_z: Option<f32>,
-    pub vel_x: Option<f32>,
-    pub vel_y: Option<f32>,
-    pub vel_z: Option<f32>,
-    pub quat_w: Option<f32>,
-    pub quat_x: Option<f32>,
-    pub quat_y: Option<f32>,
-    pub quat_z: Option<f32>,
-    pub ang_vel_x: Option<f32>,
-    pub ang_vel_y: Option<f32>,
-    pub ang_vel_z: Option<f32>,
+time_series_columns! {
+    #[derive(Debug, Clone, Copy, Default, PartialEq)]
+    pub struct TimeSeriesBallData => TimeSeriesBallColumns {
+        pub is_sleeping: Option<bool>,
+        pub hit_team_num: Option<u8>,
+        pub pos_x: Option<f32>,
+        pub pos_y: Option<f32>,
+        pub pos_z: Option<f32>,
+        pub vel_x: Option<f32>,
+        pub vel_y: Option<f32>,
+        pub vel_z: Option<f32>,
+        pub quat_w: Option<f32>,
+        pub quat_x: Option<f32>,
+        pub quat_y: Option<f32>,
+        pub quat_z: Option<f32>,
+        pub ang_vel_x: Option<f32>,
+        pub ang_vel_y: Option<f32>,
+        pub ang_vel_z: Option<f32>,
+    }
 }
 
 impl TimeSeriesBallData {
-    pub fn from(actor: &Actor, replay_version: i32) -> Self {
+    pub fn from(actor: &Actor, replay_version: i32) -> Result<Self, ActorHandlerError> {
         let mut hit_team_num = None;
         let attributes = actor.attributes.borrow();
-        if let Some(Attribute::Byte(_hit_team_num)) = attributes.get("TAGame.Ball_TA:HitTeamNum") {
+        if let Some(Attribute::Byte(_hit_team_num)) = attributes.get(AttributeKey::HitTeamNum) {
             hit_team_num = Some(*_hit_team_num);
         }
-        let rigid_body_data = RigidBodyData::from(actor, &attributes, replay_version);
+        let rigid_body_data = RigidBodyData::from(actor, &attributes, replay_version)?;
 
-        TimeSeriesBallData {
+        Ok(TimeSeriesBallData {
             hit_team_num,
             is_sleeping: rigid_body_data.is_sleeping,
             pos_x: rigid_body_data.pos_x,
@@ -64,6 +73,6 @@ impl TimeSeriesBallData {
             ang_vel_x: rigid_body_data.ang_vel_x,
             ang_vel_y: rigid_body_data.ang_vel_y,
             ang_vel_z: rigid_body_data.ang_vel_z,
-        }
+        })
     }
 }
@@ -1,12 +1,25 @@
 use crate::frame_parser::{Actor, FrameParser};
+use boxcars::ActorId;
 use std::slice::Iter;
+use thiserror::Error;
 
 pub trait ActorHandler<'a> {
     fn new(frame_parser: &'a FrameParser) -> Self
     where
         Self: Sized;
 
-    fn update(&mut self, actor: &Actor, frame_number: usize, time: f32, delta: f32);
+    fn update(
+        &mut self,
+        actor: &Actor,
+        frame_number: usize,
+        time: f32,
+        delta: f32,
+    ) -> Result<(), ActorHandlerError>;
+
+    /// Called with the actor's final attributes when it is deleted.
+    fn delete(&mut self, _actor: &Actor, _frame_number: usize) -> Result<(), ActorHandlerError> {
+        Ok(())
+    }
 
     fn priority(&self) -> ActorHandlerPriority {
         ActorHandlerPriority::Standard
@@ -26,3 +39,19 @@ impl ActorHandlerPriority {
         ACTOR_HANDLER_PRIORITIES.iter()
     }
 }
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ActorHandlerError {
+    #[error("cannot parse rigid body data for replay version {0} (< 2)")]
+    UnsupportedReplayVersion(i32),
+    #[error("rigid body actor has no initial location")]
+    MissingInitialLocation,
+    #[error("could not get UniqueId attribute")]
+    MissingUniqueId,
+    #[error("could not find player actor for car actor {0}")]
+    MissingCarPlayer(ActorId),
+    #[error("could not find unique id for player actor {0}")]
+    MissingPlayerUniqueId(ActorId),
+    #[error("custom handler failed: {0}")]
+    CustomHandlerError(String),
+}
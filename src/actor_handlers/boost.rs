@@ -1,7 +1,6 @@
-use crate::actor_handlers::ActorHandler;
+use crate::actor_handlers::{ActorHandler, ActorHandlerError, AttributeKey};
 use crate::frame_parser::{Actor, FrameParser};
 use boxcars::Attribute;
-use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct BoostHandler<'a> {
@@ -13,45 +12,44 @@ impl<'a> ActorHandler<'a> for BoostHandler<'a> {
         Self { frame_parser }
     }
 
-    fn update(&mut self, actor: &Actor, frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         let attributes = actor.attributes.borrow();
 
         if let Some(Attribute::ActiveActor(active_actor)) =
-            attributes.get("TAGame.CarComponent_TA:Vehicle")
+            attributes.get(AttributeKey::CarComponentVehicle)
         {
             let car_actor_id = active_actor.actor;
             let car_ids_to_player_ids = self.frame_parser.car_ids_to_player_ids.borrow();
             if let Some(player_actor_id) = car_ids_to_player_ids.get(&car_actor_id) {
                 let boost_data = TimeSeriesBoostData::from(actor);
-                let mut players_data = self
-                    .frame_parser
-                    .players_time_series_boost_data
-                    .borrow_mut();
+                let mut frame_snapshot = self.frame_parser.current_frame_snapshot.borrow_mut();
 
                 let players_wrapped_unique_id =
                     self.frame_parser.players_wrapped_unique_id.borrow();
-                let player_wrapped_unique_id =
-                    players_wrapped_unique_id.get(player_actor_id).unwrap();
-                match players_data.get_mut(player_wrapped_unique_id) {
-                    Some(player_data) => {
-                        player_data.insert(frame_number, boost_data);
-                    }
-                    None => {
-                        let mut player_data =
-                            HashMap::with_capacity(self.frame_parser.frame_count - frame_number);
-                        player_data.insert(frame_number, boost_data);
-                        players_data.insert(player_wrapped_unique_id.clone(), player_data);
-                    }
-                }
+                let player_wrapped_unique_id = players_wrapped_unique_id
+                    .get(player_actor_id)
+                    .ok_or(ActorHandlerError::MissingPlayerUniqueId(*player_actor_id))?;
+                frame_snapshot
+                    .players_boost_data
+                    .insert(player_wrapped_unique_id.clone(), boost_data);
             }
         }
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct TimeSeriesBoostData {
-    pub boost_is_active: Option<bool>,
-    pub boost_amount: Option<f32>,
+time_series_columns! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct TimeSeriesBoostData => TimeSeriesBoostColumns {
+        pub boost_is_active: Option<bool>,
+        pub boost_amount: Option<f32>,
+    }
 }
 
 impl TimeSeriesBoostData {
@@ -61,13 +59,11 @@ impl TimeSeriesBoostData {
         let mut boost_is_active = None;
         let mut boost_amount = None;
         if let Some(Attribute::Byte(_boost_is_active_int)) =
-            attributes.get("TAGame.CarComponent_TA:ReplicatedActive")
+            attributes.get(AttributeKey::CarComponentActive)
         {
             boost_is_active = Some(*_boost_is_active_int & 1 != 0); // Boost is active when the integer is odd.
         }
-        if let Some(Attribute::Byte(_boost_amount)) =
-            attributes.get("TAGame.CarComponent_Boost_TA:ReplicatedBoostAmount")
-        {
+        if let Some(Attribute::Byte(_boost_amount)) = attributes.get(AttributeKey::BoostAmount) {
             boost_amount = Some(*_boost_amount as f32 / 2.55);
         }
         TimeSeriesBoostData {
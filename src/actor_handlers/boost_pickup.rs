@@ -1,76 +1,91 @@
-use crate::actor_handlers::ActorHandler;
+use crate::actor_handlers::{ActorHandler, ActorHandlerError, AttributeKey, WrappedUniqueId};
 use crate::frame_parser::{Actor, FrameParser};
-use boxcars::Attribute;
-use std::collections::HashMap;
+use boxcars::{ActorId, Attribute};
 
+/// Handles boost pad actors, recording each pickup replicated by the pad.
 #[derive(Debug, Clone)]
 pub struct BoostPickupHandler<'a> {
     frame_parser: &'a FrameParser,
-    _last_picked_up_int: u8,
+    last_picked_up_int: Option<u8>,
+    last_is_picked_up: bool,
 }
 
 impl<'a> ActorHandler<'a> for BoostPickupHandler<'a> {
     fn new(frame_parser: &'a FrameParser) -> Self {
         Self {
             frame_parser,
-            _last_picked_up_int: 0,
+            last_picked_up_int: None,
+            last_is_picked_up: false,
         }
     }
 
-    fn update(&mut self, actor: &Actor, frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         let attributes = actor.attributes.borrow();
 
-        if let Some(Attribute::PickupNew(pickup_new)) =
-            attributes.get("TAGame.VehiclePickup_TA:NewReplicatedPickupData")
-        {
-            if let Some(car_actor_id) = pickup_new.instigator {
-                let car_ids_to_player_ids = self.frame_parser.car_ids_to_player_ids.borrow();
-                if let Some(player_actor_id) = car_ids_to_player_ids.get(&car_actor_id) {
-                    if pickup_new.picked_up != self._last_picked_up_int {
-                        self._last_picked_up_int = pickup_new.picked_up;
-                        let mut players_data = self
-                            .frame_parser
-                            .players_time_series_boost_pickup_data
-                            .borrow_mut();
-                        match players_data.get_mut(&player_actor_id) {
-                            Some(player_data) => {
-                                player_data.insert(frame_number, true);
-                            }
-                            None => {
-                                let mut player_data = HashMap::with_capacity(
-                                    self.frame_parser.frame_count - frame_number,
-                                );
-                                player_data.insert(frame_number, true);
-                                players_data.insert(*player_actor_id, player_data);
-                            }
-                        }
-                    }
+        let (instigator, is_new_pickup) =
+            match attributes.get(AttributeKey::NewReplicatedPickupData) {
+                Some(Attribute::PickupNew(pickup_new)) => {
+                    // The picked up integer changes on every pickup.
+                    // Its first replicated value is the pad's initial state, not a pickup.
+                    let is_new_pickup = matches!(
+                        self.last_picked_up_int,
+                        Some(last_picked_up_int) if last_picked_up_int != pickup_new.picked_up
+                    );
+                    self.last_picked_up_int = Some(pickup_new.picked_up);
+                    (pickup_new.instigator, is_new_pickup)
                 }
-            };
-        } else if let Some(Attribute::Pickup(pickup)) =
-            attributes.get("TAGame.VehiclePickup_TA:ReplicatedPickupData")
-        {
-            if let Some(car_actor_id) = pickup.instigator {
-                let car_ids_to_player_ids = self.frame_parser.car_ids_to_player_ids.borrow();
-                if let Some(player_actor_id) = car_ids_to_player_ids.get(&car_actor_id) {
-                    let mut players_data = self
-                        .frame_parser
-                        .players_time_series_boost_pickup_data
-                        .borrow_mut();
-                    match players_data.get_mut(&player_actor_id) {
-                        Some(player_data) => {
-                            player_data.insert(frame_number, pickup.picked_up);
-                        }
-                        None => {
-                            let mut player_data = HashMap::with_capacity(
-                                self.frame_parser.frame_count - frame_number,
-                            );
-                            player_data.insert(frame_number, pickup.picked_up);
-                            players_data.insert(*player_actor_id, player_data);
-                        }
+                _ => match attributes.get(AttributeKey::ReplicatedPickupData) {
+                    Some(Attribute::Pickup(pickup)) => {
+                        let is_new_pickup = pickup.picked_up && !self.last_is_picked_up;
+                        self.last_is_picked_up = pickup.picked_up;
+                        (pickup.instigator, is_new_pickup)
                     }
-                }
+                    _ => return Ok(()),
+                },
             };
+        if !is_new_pickup {
+            return Ok(());
         }
+
+        if let Some(car_actor_id) = instigator {
+            let car_ids_to_player_ids = self.frame_parser.car_ids_to_player_ids.borrow();
+            if let Some(player_actor_id) = car_ids_to_player_ids.get(&car_actor_id) {
+                let players_wrapped_unique_id =
+                    self.frame_parser.players_wrapped_unique_id.borrow();
+                let player_wrapped_unique_id = players_wrapped_unique_id
+                    .get(player_actor_id)
+                    .ok_or(ActorHandlerError::MissingPlayerUniqueId(*player_actor_id))?;
+                let pad_location = actor
+                    .new_actor
+                    .initial_trajectory
+                    .location
+                    .map(|location| [location.x as f32, location.y as f32, location.z as f32]);
+                self.frame_parser
+                    .boost_pickups_data
+                    .borrow_mut()
+                    .push(BoostPickupData {
+                        frame_number,
+                        pad_actor_id: actor.new_actor.actor_id,
+                        pad_location,
+                        player_wrapped_unique_id: player_wrapped_unique_id.clone(),
+                    });
+            }
+        }
+        Ok(())
     }
 }
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoostPickupData {
+    pub frame_number: usize,
+    pub pad_actor_id: ActorId,
+    /// The pad actor's spawn location, which most replays do not include.
+    pub pad_location: Option<[f32; 3]>,
+    pub player_wrapped_unique_id: WrappedUniqueId,
+}
@@ -0,0 +1,99 @@
+use crate::actor_handlers::{ActorAttributes, ActorHandler, ActorHandlerError, AttributeKey};
+use crate::frame_parser::{Actor, FrameParser};
+use boxcars::Attribute;
+use serde::Serialize;
+
+/// Handles camera settings actors, which are linked to their player through the player's PRI actor.
+#[derive(Debug, Clone)]
+pub struct CameraSettingsHandler<'a> {
+    frame_parser: &'a FrameParser,
+}
+
+impl<'a> ActorHandler<'a> for CameraSettingsHandler<'a> {
+    fn new(frame_parser: &'a FrameParser) -> Self {
+        Self { frame_parser }
+    }
+
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
+        let attributes = actor.attributes.borrow();
+
+        if let Some(Attribute::ActiveActor(active_actor)) =
+            attributes.get(AttributeKey::CameraSettingsPlayer)
+        {
+            let player_actor_id = active_actor.actor;
+            let players_wrapped_unique_id = self.frame_parser.players_wrapped_unique_id.borrow();
+            if let Some(player_wrapped_unique_id) = players_wrapped_unique_id.get(&player_actor_id)
+            {
+                if let Some(camera_settings) = CameraSettings::from(&attributes) {
+                    self.frame_parser
+                        .players_camera_settings
+                        .borrow_mut()
+                        .insert(player_wrapped_unique_id.clone(), camera_settings);
+                }
+                self.frame_parser
+                    .current_frame_snapshot
+                    .borrow_mut()
+                    .players_camera_data
+                    .insert(
+                        player_wrapped_unique_id.clone(),
+                        TimeSeriesCameraData::from(&attributes),
+                    );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A player's camera settings, from their profile.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CameraSettings {
+    pub fov: f32,
+    pub height: f32,
+    pub angle: f32,
+    pub distance: f32,
+    pub stiffness: f32,
+    pub swivel: f32,
+    pub transition: Option<f32>,
+}
+
+impl CameraSettings {
+    pub fn from(attributes: &ActorAttributes) -> Option<Self> {
+        match attributes.get(AttributeKey::CameraProfileSettings) {
+            Some(Attribute::CamSettings(cam_settings)) => Some(Self {
+                fov: cam_settings.fov,
+                height: cam_settings.height,
+                angle: cam_settings.angle,
+                distance: cam_settings.distance,
+                stiffness: cam_settings.stiffness,
+                swivel: cam_settings.swivel,
+                transition: cam_settings.transition,
+            }),
+            _ => None,
+        }
+    }
+}
+
+time_series_columns! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct TimeSeriesCameraData => TimeSeriesCameraColumns {
+        pub ball_cam: Option<bool>,
+    }
+}
+
+impl TimeSeriesCameraData {
+    pub fn from(attributes: &ActorAttributes) -> Self {
+        let mut ball_cam = None;
+        if let Some(Attribute::Boolean(_ball_cam)) =
+            attributes.get(AttributeKey::UsingSecondaryCamera)
+        {
+            ball_cam = Some(*_ball_cam);
+        }
+        TimeSeriesCameraData { ball_cam }
+    }
+}
@@ -1,4 +1,6 @@
-use crate::actor_handlers::{ActorHandler, RigidBodyData, WrappedUniqueId};
+use crate::actor_handlers::{
+    ActorAttributes, ActorHandler, ActorHandlerError, AttributeKey, RigidBodyData, WrappedUniqueId,
+};
 use crate::frame_parser::{Actor, FrameParser};
 use boxcars::attributes::Demolish;
 use boxcars::{ActorId, Attribute};
@@ -15,11 +17,17 @@ impl<'a> ActorHandler<'a> for CarHandler<'a> {
         Self { frame_parser }
     }
 
-    fn update(&mut self, actor: &Actor, frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         let car_actor_id = actor.new_actor.actor_id;
         let mut attributes = actor.attributes.borrow_mut();
         if let Some(Attribute::ActiveActor(active_actor)) =
-            attributes.get("Engine.Pawn:PlayerReplicationInfo")
+            attributes.get(AttributeKey::PawnPlayerReplicationInfo)
         {
             let player_actor_id = active_actor.actor;
             let players_wrapped_unique_id = self.frame_parser.players_wrapped_unique_id.borrow();
@@ -32,25 +40,17 @@ impl<'a> ActorHandler<'a> for CarHandler<'a> {
 
                 // Add time-series car data
                 let car_data =
-                    TimeSeriesCarData::from(actor, &attributes, self.frame_parser.replay_version); // attributes passed here as borrowed mut above.
-                let mut players_data = self.frame_parser.players_time_series_car_data.borrow_mut();
-                match players_data.get_mut(player_wrapped_unique_id) {
-                    Some(player_data) => {
-                        player_data.insert(frame_number, car_data);
-                    }
-                    None => {
-                        let mut player_data =
-                            HashMap::with_capacity(self.frame_parser.frame_count - frame_number);
-                        player_data.insert(frame_number, car_data);
-                        players_data.insert(player_wrapped_unique_id.clone(), player_data);
-                    }
-                }
+                    TimeSeriesCarData::from(actor, &attributes, self.frame_parser.replay_version)?; // attributes passed here as borrowed mut above.
+                let mut frame_snapshot = self.frame_parser.current_frame_snapshot.borrow_mut();
+                frame_snapshot
+                    .players_car_data
+                    .insert(player_wrapped_unique_id.clone(), car_data);
             }
         }
 
         // Add demos
         if let Some(Attribute::Demolish(demolish)) =
-            attributes.get("TAGame.Car_TA:ReplicatedDemolish")
+            attributes.get(AttributeKey::ReplicatedDemolish)
         {
             let players_wrapped_unique_id = self.frame_parser.players_wrapped_unique_id.borrow();
             let car_ids_to_player_ids = self.frame_parser.car_ids_to_player_ids.borrow();
@@ -60,62 +60,61 @@ impl<'a> ActorHandler<'a> for CarHandler<'a> {
                 frame_number,
                 &car_ids_to_player_ids,
                 &players_wrapped_unique_id,
-            ) {
+            )? {
                 demos_data.push(demo_data);
             }
-            attributes.remove("TAGame.Car_TA:ReplicatedDemolish");
+            attributes.remove(AttributeKey::ReplicatedDemolish);
         }
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct TimeSeriesCarData {
-    pub throttle: Option<u8>,
-    pub steer: Option<u8>,
-    pub handbrake: Option<u8>,
-    pub is_sleeping: Option<bool>,
-    pub pos_x: Option<f32>,
-    pub pos_y: Option<f32>,
-    pub pos_z: Option<f32>,
-    pub vel_x: Option<f32>,
-    pub vel_y: Option<f32>,
-    pub vel_z: Option<f32>,
-    pub quat_w: Option<f32>,
-    pub quat_x: Option<f32>,
-    pub quat_y: Option<f32>,
-    pub quat_z: Option<f32>,
-    pub ang_vel_x: Option<f32>,
-    pub ang_vel_y: Option<f32>,
-    pub ang_vel_z: Option<f32>,
+time_series_columns! {
+    #[derive(Debug, Clone, Copy, Default, PartialEq)]
+    pub struct TimeSeriesCarData => TimeSeriesCarColumns {
+        pub throttle: Option<u8>,
+        pub steer: Option<u8>,
+        pub handbrake: Option<u8>,
+        pub is_sleeping: Option<bool>,
+        pub pos_x: Option<f32>,
+        pub pos_y: Option<f32>,
+        pub pos_z: Option<f32>,
+        pub vel_x: Option<f32>,
+        pub vel_y: Option<f32>,
+        pub vel_z: Option<f32>,
+        pub quat_w: Option<f32>,
+        pub quat_x: Option<f32>,
+        pub quat_y: Option<f32>,
+        pub quat_z: Option<f32>,
+        pub ang_vel_x: Option<f32>,
+        pub ang_vel_y: Option<f32>,
+        pub ang_vel_z: Option<f32>,
+    }
 }
 
 impl TimeSeriesCarData {
     pub fn from(
         actor: &Actor,
-        attributes: &HashMap<String, boxcars::Attribute>,
+        attributes: &ActorAttributes,
         replay_version: i32,
-    ) -> Self {
+    ) -> Result<Self, ActorHandlerError> {
         let mut throttle = None;
         let mut steer = None;
         let mut handbrake = None;
 
-        if let Some(Attribute::Byte(_throttle)) =
-            attributes.get("TAGame.Vehicle_TA:ReplicatedThrottle")
-        {
+        if let Some(Attribute::Byte(_throttle)) = attributes.get(AttributeKey::Throttle) {
             throttle = Some(*_throttle);
         }
-        if let Some(Attribute::Byte(_steer)) = attributes.get("TAGame.Vehicle_TA:ReplicatedSteer") {
+        if let Some(Attribute::Byte(_steer)) = attributes.get(AttributeKey::Steer) {
             steer = Some(*_steer);
         }
-        if let Some(Attribute::Byte(_handbrake)) =
-            attributes.get("TAGame.Vehicle_TA:bReplicatedHandbrake")
-        {
+        if let Some(Attribute::Byte(_handbrake)) = attributes.get(AttributeKey::Handbrake) {
             handbrake = Some(*_handbrake);
         }
 
-        let rigid_body_data = RigidBodyData::from(actor, attributes, replay_version);
+        let rigid_body_data = RigidBodyData::from(actor, attributes, replay_version)?;
 
-        TimeSeriesCarData {
+        Ok(TimeSeriesCarData {
             throttle,
             steer,
             handbrake,
@@ -133,7 +132,7 @@ impl TimeSeriesCarData {
             ang_vel_x: rigid_body_data.ang_vel_x,
             ang_vel_y: rigid_body_data.ang_vel_y,
             ang_vel_z: rigid_body_data.ang_vel_z,
-        }
+        })
     }
 }
 
@@ -150,25 +149,29 @@ impl DemoData {
         frame_number: usize,
         car_ids_to_player_ids: &HashMap<ActorId, ActorId>,
         players_wrapped_unique_id: &HashMap<ActorId, WrappedUniqueId>,
-    ) -> Option<Self> {
+    ) -> Result<Option<Self>, ActorHandlerError> {
         if !demolish.attacker_flag {
             // Attacker flag can be false and demolish.attacker == ActorId(-1).
             // I assume this is not a player-induced demolish. Could be goal explosion or goal reset or change team.
-            return None;
+            return Ok(None);
         } else if demolish.attacker.0 == -1 {
             error!("Demo on frame {} where attacker is -1.", frame_number);
-            return None;
+            return Ok(None);
         }
-        Some(Self {
+        let get_wrapped_unique_id =
+            |car_actor_id: ActorId| -> Result<WrappedUniqueId, ActorHandlerError> {
+                let player_actor_id = car_ids_to_player_ids
+                    .get(&car_actor_id)
+                    .ok_or(ActorHandlerError::MissingCarPlayer(car_actor_id))?;
+                players_wrapped_unique_id
+                    .get(player_actor_id)
+                    .cloned()
+                    .ok_or(ActorHandlerError::MissingPlayerUniqueId(*player_actor_id))
+            };
+        Ok(Some(Self {
             frame_number,
-            attacker_wrapped_unique_id: players_wrapped_unique_id
-                .get(car_ids_to_player_ids.get(&demolish.attacker).unwrap())
-                .unwrap()
-                .clone(),
-            victim_wrapped_unique_id: players_wrapped_unique_id
-                .get(car_ids_to_player_ids.get(&demolish.victim).unwrap())
-                .unwrap()
-                .clone(),
-        })
+            attacker_wrapped_unique_id: get_wrapped_unique_id(demolish.attacker)?,
+            victim_wrapped_unique_id: get_wrapped_unique_id(demolish.victim)?,
+        }))
     }
 }
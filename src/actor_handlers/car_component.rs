@@ -0,0 +1,168 @@
+use crate::actor_handlers::{
+    ActorAttributes, ActorHandler, ActorHandlerError, AttributeKey, WrappedUniqueId,
+};
+use crate::frame_parser::{Actor, FrameParser};
+use boxcars::Attribute;
+
+#[derive(Debug, Clone)]
+pub struct JumpHandler<'a> {
+    frame_parser: &'a FrameParser,
+}
+
+impl<'a> ActorHandler<'a> for JumpHandler<'a> {
+    fn new(frame_parser: &'a FrameParser) -> Self {
+        Self { frame_parser }
+    }
+
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
+        let attributes = actor.attributes.borrow();
+        update_car_component_data(self.frame_parser, &attributes, |data| {
+            data.jump_active = get_car_component_is_active(&attributes);
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DoubleJumpHandler<'a> {
+    frame_parser: &'a FrameParser,
+}
+
+impl<'a> ActorHandler<'a> for DoubleJumpHandler<'a> {
+    fn new(frame_parser: &'a FrameParser) -> Self {
+        Self { frame_parser }
+    }
+
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
+        let attributes = actor.attributes.borrow();
+        update_car_component_data(self.frame_parser, &attributes, |data| {
+            data.double_jump_active = get_car_component_is_active(&attributes);
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DodgeHandler<'a> {
+    frame_parser: &'a FrameParser,
+}
+
+impl<'a> ActorHandler<'a> for DodgeHandler<'a> {
+    fn new(frame_parser: &'a FrameParser) -> Self {
+        Self { frame_parser }
+    }
+
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
+        let attributes = actor.attributes.borrow();
+        update_car_component_data(self.frame_parser, &attributes, |data| {
+            data.dodge_active = get_car_component_is_active(&attributes);
+            if let Some(Attribute::Location(dodge_torque)) =
+                attributes.get(AttributeKey::DodgeTorque)
+            {
+                data.dodge_torque_x = Some(dodge_torque.x);
+                data.dodge_torque_y = Some(dodge_torque.y);
+            }
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FlipCarHandler<'a> {
+    frame_parser: &'a FrameParser,
+}
+
+impl<'a> ActorHandler<'a> for FlipCarHandler<'a> {
+    fn new(frame_parser: &'a FrameParser) -> Self {
+        Self { frame_parser }
+    }
+
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
+        let attributes = actor.attributes.borrow();
+        update_car_component_data(self.frame_parser, &attributes, |data| {
+            data.flip_car_active = get_car_component_is_active(&attributes);
+        })
+    }
+}
+
+/// Updates the car component data of the player driving the component's car (if any) on the current frame.
+fn update_car_component_data(
+    frame_parser: &FrameParser,
+    attributes: &ActorAttributes,
+    update: impl FnOnce(&mut TimeSeriesCarComponentData),
+) -> Result<(), ActorHandlerError> {
+    if let Some(player_wrapped_unique_id) =
+        get_car_component_player_wrapped_unique_id(frame_parser, attributes)?
+    {
+        let mut frame_snapshot = frame_parser.current_frame_snapshot.borrow_mut();
+        update(
+            frame_snapshot
+                .players_car_component_data
+                .entry(player_wrapped_unique_id)
+                .or_default(),
+        );
+    }
+    Ok(())
+}
+
+/// Links a car component to its player through `TAGame.CarComponent_TA:Vehicle`, as with boost.
+pub fn get_car_component_player_wrapped_unique_id(
+    frame_parser: &FrameParser,
+    attributes: &ActorAttributes,
+) -> Result<Option<WrappedUniqueId>, ActorHandlerError> {
+    if let Some(Attribute::ActiveActor(active_actor)) =
+        attributes.get(AttributeKey::CarComponentVehicle)
+    {
+        let car_actor_id = active_actor.actor;
+        let car_ids_to_player_ids = frame_parser.car_ids_to_player_ids.borrow();
+        if let Some(player_actor_id) = car_ids_to_player_ids.get(&car_actor_id) {
+            let players_wrapped_unique_id = frame_parser.players_wrapped_unique_id.borrow();
+            let player_wrapped_unique_id = players_wrapped_unique_id
+                .get(player_actor_id)
+                .ok_or(ActorHandlerError::MissingPlayerUniqueId(*player_actor_id))?;
+            return Ok(Some(player_wrapped_unique_id.clone()));
+        }
+    }
+    Ok(None)
+}
+
+pub fn get_car_component_is_active(attributes: &ActorAttributes) -> Option<bool> {
+    match attributes.get(AttributeKey::CarComponentActive) {
+        // Component is active when the integer is odd.
+        Some(Attribute::Byte(active_int)) => Some(*active_int & 1 != 0),
+        _ => None,
+    }
+}
+
+time_series_columns! {
+    #[derive(Debug, Clone, Copy, Default, PartialEq)]
+    pub struct TimeSeriesCarComponentData => TimeSeriesCarComponentColumns {
+        pub jump_active: Option<bool>,
+        pub double_jump_active: Option<bool>,
+        pub dodge_active: Option<bool>,
+        pub dodge_torque_x: Option<f32>,
+        pub dodge_torque_y: Option<f32>,
+        pub flip_car_active: Option<bool>,
+    }
+}
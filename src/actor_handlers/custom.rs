@@ -0,0 +1,96 @@
+use crate::actor_handlers::ActorHandler;
+use crate::frame_parser::FrameParser;
+use std::any::Any;
+use std::fmt;
+use std::fmt::Debug;
+
+/// Creates a boxed handler for a newly-spawned actor.
+/// Custom handlers are registered with a plain function, e.g.
+/// `fn create<'a>(frame_parser: &'a FrameParser) -> Box<dyn ActorHandler<'a> + 'a> { Box::new(MyHandler::new(frame_parser)) }`.
+pub type ActorHandlerConstructor = for<'a> fn(&'a FrameParser) -> Box<dyn ActorHandler<'a> + 'a>;
+
+/// A user-defined handler, bound to the actors whose object name matches `matcher`.
+/// Custom handlers run after the built-in handlers on each frame, including for actors that a built-in handler also handles.
+#[derive(Clone)]
+pub struct CustomActorHandler {
+    /// Key for the handler's output in `FrameParser.custom_outputs`.
+    /// Handlers registered with the same name share the same output.
+    pub name: String,
+    pub matcher: ObjectNameMatcher,
+    pub constructor: ActorHandlerConstructor,
+    /// Initial value of the handler's output storage.
+    pub output: Box<dyn CustomOutput>,
+}
+
+impl CustomActorHandler {
+    pub fn new<T: CustomOutput>(
+        name: &str,
+        matcher: ObjectNameMatcher,
+        constructor: ActorHandlerConstructor,
+        output: T,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            matcher,
+            constructor,
+            output: Box::new(output),
+        }
+    }
+}
+
+impl Debug for CustomActorHandler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CustomActorHandler")
+            .field("name", &self.name)
+            .field("matcher", &self.matcher)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ObjectNameMatcher {
+    Exact(String),
+    StartsWith(String),
+    EndsWith(String),
+    Contains(String),
+}
+
+impl ObjectNameMatcher {
+    pub fn matches(&self, object_name: &str) -> bool {
+        match self {
+            Self::Exact(pattern) => object_name == pattern,
+            Self::StartsWith(pattern) => object_name.starts_with(pattern),
+            Self::EndsWith(pattern) => object_name.ends_with(pattern),
+            Self::Contains(pattern) => object_name.contains(pattern),
+        }
+    }
+}
+
+/// Output storage written to by custom handlers.
+/// Implemented for any `'static` type that is `Debug + Clone + Send + Sync`.
+pub trait CustomOutput: Any + Debug + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn clone_box(&self) -> Box<dyn CustomOutput>;
+}
+
+impl<T: Any + Debug + Clone + Send + Sync> CustomOutput for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn clone_box(&self) -> Box<dyn CustomOutput> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn CustomOutput> {
+    fn clone(&self) -> Self {
+        // Dereference to clone the inner output rather than the box itself.
+        (**self).clone_box()
+    }
+}
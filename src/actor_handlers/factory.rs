@@ -1,6 +1,8 @@
 use crate::actor_handlers::{
-    ActorHandler, BallHandler, BlueTeamHandler, BoostHandler, CarHandler, GameEventHandler,
-    GameInfoHandler, OrangeTeamHandler, PlayerHandler,
+    ActorHandler, BallHandler, BlueTeamHandler, BoostHandler, BoostPickupHandler,
+    CameraSettingsHandler, CarHandler, DodgeHandler, DoubleJumpHandler, FlipCarHandler,
+    GameEventHandler, GameInfoHandler, JumpHandler, OrangeTeamHandler, PlayerHandler, PowerUp,
+    PowerUpHandler,
 };
 use crate::frame_parser::FrameParser;
 use std::cell::RefCell;
@@ -10,6 +12,7 @@ use std::collections::HashMap;
 pub struct ActorHandlerFactory<'a> {
     frame_parser: &'a FrameParser,
     object_id_to_handler_cache: RefCell<HashMap<boxcars::ObjectId, ActorKind>>,
+    object_id_to_custom_handlers_cache: RefCell<HashMap<boxcars::ObjectId, Vec<usize>>>,
 }
 
 impl<'a> ActorHandlerFactory<'a> {
@@ -17,14 +20,15 @@ impl<'a> ActorHandlerFactory<'a> {
         Self {
             frame_parser,
             object_id_to_handler_cache: RefCell::new(HashMap::default()),
+            object_id_to_custom_handlers_cache: RefCell::new(HashMap::default()),
         }
     }
 
     pub fn get_handler(
-        &'a self,
+        &self,
         object_id: boxcars::ObjectId,
         replay_objects: &[String],
-    ) -> Option<Box<dyn ActorHandler + 'a>> {
+    ) -> Option<Box<dyn ActorHandler<'a> + 'a>> {
         let mut object_id_to_handler_cache = self.object_id_to_handler_cache.borrow_mut();
         let actor_kind = match object_id_to_handler_cache.get(&object_id) {
             Some(actor_kind) => *actor_kind,
@@ -37,10 +41,42 @@ impl<'a> ActorHandlerFactory<'a> {
         self.get_handler_for_actor_kind(actor_kind)
     }
 
+    /// Creates a handler for each registered custom handler matching the object.
+    /// These run in addition to any built-in handler for the actor.
+    pub fn get_custom_handlers(
+        &self,
+        object_id: boxcars::ObjectId,
+        replay_objects: &[String],
+    ) -> Vec<Box<dyn ActorHandler<'a> + 'a>> {
+        let custom_actor_handlers = &self.frame_parser.custom_actor_handlers;
+        if custom_actor_handlers.is_empty() {
+            return vec![];
+        }
+        let mut object_id_to_custom_handlers_cache =
+            self.object_id_to_custom_handlers_cache.borrow_mut();
+        let indices = object_id_to_custom_handlers_cache
+            .entry(object_id)
+            .or_insert_with(|| {
+                let object_name = &replay_objects[usize::from(object_id)];
+                custom_actor_handlers
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, custom_actor_handler)| {
+                        custom_actor_handler.matcher.matches(object_name)
+                    })
+                    .map(|(index, _)| index)
+                    .collect()
+            });
+        indices
+            .iter()
+            .map(|index| (custom_actor_handlers[*index].constructor)(self.frame_parser))
+            .collect()
+    }
+
     pub fn get_handler_for_actor_kind(
-        &'a self,
+        &self,
         actor_kind: ActorKind,
-    ) -> Option<Box<dyn ActorHandler + 'a>> {
+    ) -> Option<Box<dyn ActorHandler<'a> + 'a>> {
         match actor_kind {
             ActorKind::GameInfo => Some(Box::new(GameInfoHandler::new(self.frame_parser))),
             ActorKind::GameEvent => Some(Box::new(GameEventHandler::new(self.frame_parser))),
@@ -50,6 +86,18 @@ impl<'a> ActorHandlerFactory<'a> {
             ActorKind::Player => Some(Box::new(PlayerHandler::new(self.frame_parser))),
             ActorKind::Car => Some(Box::new(CarHandler::new(self.frame_parser))),
             ActorKind::Boost => Some(Box::new(BoostHandler::new(self.frame_parser))),
+            ActorKind::BoostPickup => Some(Box::new(BoostPickupHandler::new(self.frame_parser))),
+            ActorKind::Jump => Some(Box::new(JumpHandler::new(self.frame_parser))),
+            ActorKind::DoubleJump => Some(Box::new(DoubleJumpHandler::new(self.frame_parser))),
+            ActorKind::Dodge => Some(Box::new(DodgeHandler::new(self.frame_parser))),
+            ActorKind::FlipCar => Some(Box::new(FlipCarHandler::new(self.frame_parser))),
+            ActorKind::PowerUp(power_up) => Some(Box::new(PowerUpHandler::for_power_up(
+                self.frame_parser,
+                power_up,
+            ))),
+            ActorKind::CameraSettings => {
+                Some(Box::new(CameraSettingsHandler::new(self.frame_parser)))
+            }
             ActorKind::NotHandled => None,
         }
     }
@@ -65,6 +113,13 @@ pub enum ActorKind {
     Player,
     Car,
     Boost,
+    BoostPickup,
+    Jump,
+    DoubleJump,
+    Dodge,
+    FlipCar,
+    PowerUp(PowerUp),
+    CameraSettings,
     NotHandled,
 }
 
@@ -87,6 +142,20 @@ impl ActorKind {
             Self::Car
         } else if object_name == "Archetypes.CarComponents.CarComponent_Boost" {
             Self::Boost
+        } else if object_name.contains("VehiclePickup_Boost_TA") {
+            Self::BoostPickup
+        } else if object_name == "Archetypes.CarComponents.CarComponent_Jump" {
+            Self::Jump
+        } else if object_name == "Archetypes.CarComponents.CarComponent_DoubleJump" {
+            Self::DoubleJump
+        } else if object_name == "Archetypes.CarComponents.CarComponent_Dodge" {
+            Self::Dodge
+        } else if object_name == "Archetypes.CarComponents.CarComponent_FlipCar" {
+            Self::FlipCar
+        } else if let Some(power_up) = PowerUp::from_object_name(object_name) {
+            Self::PowerUp(power_up)
+        } else if object_name == "TAGame.Default__CameraSettingsActor_TA" {
+            Self::CameraSettings
         } else {
             Self::NotHandled
         }
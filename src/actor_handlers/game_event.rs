@@ -1,4 +1,4 @@
-use crate::actor_handlers::ActorHandler;
+use crate::actor_handlers::{ActorHandler, ActorHandlerError, AttributeKey};
 use crate::frame_parser::{Actor, FrameParser};
 use boxcars::Attribute;
 
@@ -12,7 +12,13 @@ impl<'a> ActorHandler<'a> for GameEventHandler<'a> {
         Self { frame_parser }
     }
 
-    fn update(&mut self, actor: &Actor, frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         let attributes = actor.attributes.borrow();
 
         self.frame_parser
@@ -20,17 +26,21 @@ impl<'a> ActorHandler<'a> for GameEventHandler<'a> {
             .replace(Some(attributes.clone())); // TODO: Optimise by avoiding premature clones? (i.e. Only clone final actor)
 
         let data = TimeSeriesGameEventData::from(actor);
-        let mut game_event_data = self.frame_parser.time_series_game_event_data.borrow_mut();
-        game_event_data.insert(frame_number, data);
+        let mut frame_snapshot = self.frame_parser.current_frame_snapshot.borrow_mut();
+        frame_snapshot.game_event_data = Some(data);
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct TimeSeriesGameEventData {
-    pub seconds_remaining: Option<i32>,
-    pub replicated_game_state_time_remaining: Option<i32>,
-    pub is_overtime: Option<bool>,
-    pub ball_has_been_hit: Option<bool>,
+time_series_columns! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct TimeSeriesGameEventData => TimeSeriesGameEventColumns {
+        pub seconds_remaining: Option<i32>,
+        pub replicated_game_state_time_remaining: Option<i32>,
+        pub is_overtime: Option<bool>,
+        pub ball_has_been_hit: Option<bool>,
+        pub match_ended: Option<bool>,
+    }
 }
 
 impl TimeSeriesGameEventData {
@@ -41,32 +51,35 @@ impl TimeSeriesGameEventData {
         let mut replicated_game_state_time_remaining = None;
         let mut is_overtime = None;
         let mut ball_has_been_hit = None;
+        let mut match_ended = None;
 
         if let Some(Attribute::Int(_seconds_remaining)) =
-            attributes.get("TAGame.GameEvent_Soccar_TA:SecondsRemaining")
+            attributes.get(AttributeKey::SecondsRemaining)
         {
             seconds_remaining = Some(*_seconds_remaining);
         }
         if let Some(Attribute::Int(_replicated_game_state_time_remaining)) =
-            attributes.get("TAGame.GameEvent_TA:ReplicatedGameStateTimeRemaining")
+            attributes.get(AttributeKey::GameStateTimeRemaining)
         {
             replicated_game_state_time_remaining = Some(*_replicated_game_state_time_remaining);
         }
-        if let Some(Attribute::Boolean(_is_overtime)) =
-            attributes.get("TAGame.GameEvent_Soccar_TA:bOverTime")
-        {
+        if let Some(Attribute::Boolean(_is_overtime)) = attributes.get(AttributeKey::OverTime) {
             is_overtime = Some(*_is_overtime);
         }
         if let Some(Attribute::Boolean(_ball_has_been_hit)) =
-            attributes.get("TAGame.GameEvent_Soccar_TA:bBallHasBeenHit")
+            attributes.get(AttributeKey::BallHasBeenHit)
         {
             ball_has_been_hit = Some(*_ball_has_been_hit);
         }
+        if let Some(Attribute::Boolean(_match_ended)) = attributes.get(AttributeKey::MatchEnded) {
+            match_ended = Some(*_match_ended);
+        }
         Self {
             seconds_remaining,
             replicated_game_state_time_remaining,
             is_overtime,
             ball_has_been_hit,
+            match_ended,
         }
     }
 }
@@ -1,4 +1,4 @@
-use crate::actor_handlers::ActorHandler;
+use crate::actor_handlers::{ActorHandler, ActorHandlerError};
 use crate::frame_parser::{Actor, FrameParser};
 
 #[derive(Debug, Clone)]
@@ -11,11 +11,18 @@ impl<'a> ActorHandler<'a> for GameInfoHandler<'a> {
         Self { frame_parser }
     }
 
-    fn update(&mut self, actor: &Actor, _frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         let attributes = actor.attributes.borrow();
 
         self.frame_parser
             .game_info_actor
             .replace(Some(attributes.clone())); // TODO: Optimise by avoiding premature clones? (i.e. Only clone final actor)
+        Ok(())
     }
 }
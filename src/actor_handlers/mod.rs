@@ -1,21 +1,33 @@
+pub mod attributes;
 pub mod ball;
 pub mod base;
 pub mod boost;
+pub mod boost_pickup;
+pub mod camera_settings;
 pub mod car;
+pub mod car_component;
+pub mod custom;
 pub mod factory;
 pub mod game_event;
 pub mod game_info;
 pub mod player;
+pub mod power_up;
 pub mod team;
 pub mod utils;
 
+pub use self::attributes::*;
 pub use self::ball::*;
 pub use self::base::*;
 pub use self::boost::*;
+pub use self::boost_pickup::*;
+pub use self::camera_settings::*;
 pub use self::car::*;
+pub use self::car_component::*;
+pub use self::custom::*;
 pub use self::factory::*;
 pub use self::game_event::*;
 pub use self::game_info::*;
 pub use self::player::*;
+pub use self::power_up::*;
 pub use self::team::*;
 pub use self::utils::*;
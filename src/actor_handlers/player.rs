@@ -1,7 +1,9 @@
-use crate::actor_handlers::{ActorHandler, ActorHandlerPriority, WrappedUniqueId};
+use crate::actor_handlers::{
+    ActorAttributes, ActorHandler, ActorHandlerError, ActorHandlerPriority, AttributeKey,
+    WrappedUniqueId,
+};
 use crate::frame_parser::{Actor, FrameParser};
 use boxcars::Attribute;
-use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct PlayerHandler<'a> {
@@ -17,12 +19,23 @@ impl<'a> ActorHandler<'a> for PlayerHandler<'a> {
         }
     }
 
-    fn update(&mut self, actor: &Actor, frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         let actor_id = actor.new_actor.actor_id;
         let attributes = actor.attributes.borrow();
 
-        if self.wrapped_unique_id == None {
-            let wrapped_unique_id = WrappedUniqueId::from(&attributes);
+        if self.wrapped_unique_id.is_none() {
+            let wrapped_unique_id = match WrappedUniqueId::from(actor_id, &attributes) {
+                Ok(wrapped_unique_id) => wrapped_unique_id,
+                // Retried until the unique id (or a bot's name) is replicated.
+                Err(ActorHandlerError::MissingUniqueId) => return Ok(()),
+                Err(err) => return Err(err),
+            };
             self.wrapped_unique_id = Some(wrapped_unique_id.clone());
             let mut players_wrapped_unique_id =
                 self.frame_parser.players_wrapped_unique_id.borrow_mut();
@@ -31,60 +44,47 @@ impl<'a> ActorHandler<'a> for PlayerHandler<'a> {
 
         // Add time-series data
         let data = TimeSeriesPlayerData::from(actor);
-        let mut players_data = self
-            .frame_parser
-            .players_time_series_player_data
-            .borrow_mut();
         let wrapped_unique_id = self.wrapped_unique_id.as_ref().unwrap().clone();
-        match players_data.get_mut(&wrapped_unique_id) {
-            Some(player_data) => {
-                player_data.insert(frame_number, data);
-            }
-            None => {
-                let mut player_data =
-                    HashMap::with_capacity(self.frame_parser.frame_count - frame_number);
-                player_data.insert(frame_number, data);
-                players_data.insert(wrapped_unique_id.clone(), player_data);
-            }
-        }
+        self.frame_parser
+            .current_frame_snapshot
+            .borrow_mut()
+            .players_player_data
+            .insert(wrapped_unique_id.clone(), data);
 
-        // Record team every 500 frames.
-        if frame_number > 500 && frame_number % 500 == 1 {
-            let teams_actor = self.frame_parser.teams_data.borrow();
-            let mut players_teams = self.frame_parser.players_teams.borrow_mut();
-            if let Some(Attribute::ActiveActor(team_active_actor)) =
-                attributes.get("Engine.PlayerReplicationInfo:Team")
-            {
-                let team_actor_id = team_active_actor.actor;
-                if let Some(is_orange) = teams_actor
-                    .get(&team_actor_id)
-                    .map(|team_data| team_data.is_orange)
-                {
-                    let player_teams = players_teams
-                        .entry(wrapped_unique_id.clone())
-                        .or_insert_with(HashMap::new);
-                    player_teams
-                        .entry(is_orange)
-                        .and_modify(|count| *count += 1)
-                        .or_insert(1);
-                }
-            };
+        // Update actor data (only on final frame to avoid unnecessary cloning).
+        // PRIs deleted before then (e.g. leavers) are stored in `delete`.
+        if frame_number == self.frame_parser.frame_count - 1 {
+            self.store_player_actor(&attributes);
         }
+        Ok(())
+    }
 
-        // Update actor data (only on final frame to avoid unnecessary cloning)
-        if frame_number == self.frame_parser.frame_count - 1 {
+    fn delete(&mut self, actor: &Actor, _frame_number: usize) -> Result<(), ActorHandlerError> {
+        self.store_player_actor(&actor.attributes.borrow());
+        Ok(())
+    }
+
+    fn priority(&self) -> ActorHandlerPriority {
+        ActorHandlerPriority::First
+    }
+}
+
+impl<'a> PlayerHandler<'a> {
+    /// Stores the PRI's attributes, unless another PRI of the player has a higher match score.
+    fn store_player_actor(&self, attributes: &ActorAttributes) {
+        if let Some(wrapped_unique_id) = self.wrapped_unique_id.clone() {
             let mut players_actor_data = self.frame_parser.players_actor.borrow_mut();
             if !players_actor_data.contains_key(&wrapped_unique_id) {
                 players_actor_data.insert(wrapped_unique_id, attributes.clone());
             } else {
-                let match_score = match attributes.get("TAGame.PRI_TA:MatchScore") {
+                let match_score = match attributes.get(AttributeKey::MatchScore) {
                     Some(Attribute::Int(match_score)) => *match_score,
                     _ => 0,
                 };
                 let existing_player_actor_data =
                     players_actor_data.get(&wrapped_unique_id).unwrap();
                 let existing_match_score =
-                    match existing_player_actor_data.get("TAGame.PRI_TA:MatchScore") {
+                    match existing_player_actor_data.get(AttributeKey::MatchScore) {
                         Some(Attribute::Int(match_score)) => *match_score,
                         _ => 0,
                     };
@@ -97,21 +97,21 @@ impl<'a> ActorHandler<'a> for PlayerHandler<'a> {
             }
         }
     }
-
-    fn priority(&self) -> ActorHandlerPriority {
-        ActorHandlerPriority::First
-    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct TimeSeriesPlayerData {
-    pub match_score: Option<i32>,
-    pub match_goals: Option<i32>,
-    pub match_assists: Option<i32>,
-    pub match_saves: Option<i32>,
-    pub match_shots: Option<i32>,
-    pub team: Option<i32>,
-    pub ping: Option<u8>,
+time_series_columns! {
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub struct TimeSeriesPlayerData => TimeSeriesPlayerColumns {
+        pub match_score: Option<i32>,
+        pub match_goals: Option<i32>,
+        pub match_assists: Option<i32>,
+        pub match_saves: Option<i32>,
+        pub match_shots: Option<i32>,
+        /// Raw team actor id, resolved to a team in `CleanedData::players_time_series_team`.
+        /// The player DataFrame's `team` column holds the resolved team (0 blue, 1 orange), not this id.
+        pub team: Option<i32>,
+        pub ping: Option<u8>,
+    }
 }
 
 impl TimeSeriesPlayerData {
@@ -126,27 +126,25 @@ impl TimeSeriesPlayerData {
         let mut team = None;
         let mut ping = None;
 
-        if let Some(Attribute::Int(_match_score)) = attributes.get("TAGame.PRI_TA:MatchScore") {
+        if let Some(Attribute::Int(_match_score)) = attributes.get(AttributeKey::MatchScore) {
             match_score = Some(*_match_score);
         }
-        if let Some(Attribute::Int(_match_goals)) = attributes.get("TAGame.PRI_TA:MatchGoals") {
+        if let Some(Attribute::Int(_match_goals)) = attributes.get(AttributeKey::MatchGoals) {
             match_goals = Some(*_match_goals);
         }
-        if let Some(Attribute::Int(_match_assists)) = attributes.get("TAGame.PRI_TA:MatchAssists") {
+        if let Some(Attribute::Int(_match_assists)) = attributes.get(AttributeKey::MatchAssists) {
             match_assists = Some(*_match_assists);
         }
-        if let Some(Attribute::Int(_match_saves)) = attributes.get("TAGame.PRI_TA:MatchSaves") {
+        if let Some(Attribute::Int(_match_saves)) = attributes.get(AttributeKey::MatchSaves) {
             match_saves = Some(*_match_saves);
         }
-        if let Some(Attribute::Int(_match_shots)) = attributes.get("TAGame.PRI_TA:MatchShots") {
+        if let Some(Attribute::Int(_match_shots)) = attributes.get(AttributeKey::MatchShots) {
             match_shots = Some(*_match_shots);
         }
-        if let Some(Attribute::ActiveActor(_team)) =
-            attributes.get("Engine.PlayerReplicationInfo:Team")
-        {
+        if let Some(Attribute::ActiveActor(_team)) = attributes.get(AttributeKey::PlayerTeam) {
             team = Some(_team.actor.0);
         }
-        if let Some(Attribute::Byte(_ping)) = attributes.get("Engine.PlayerReplicationInfo:Ping") {
+        if let Some(Attribute::Byte(_ping)) = attributes.get(AttributeKey::Ping) {
             ping = Some(*_ping);
         }
 
@@ -0,0 +1,206 @@
+use crate::actor_handlers::{
+    get_car_component_is_active, get_car_component_player_wrapped_unique_id, ActorHandler,
+    ActorHandlerError, AttributeKey, WrappedUniqueId,
+};
+use crate::frame_parser::{Actor, FrameParser};
+use boxcars::Attribute;
+use serde::Serialize;
+
+static SPECIAL_PICKUP_PREFIX: &str = "Archetypes.SpecialPickups.SpecialPickup_";
+
+/// Rumble items, named after their `SpecialPickup_*` archetypes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum PowerUp {
+    /// Freezer.
+    BallFreeze,
+    GrapplingHook,
+    /// Plunger.
+    BallLasso,
+    /// Haymaker.
+    BallSpring,
+    /// Spikes.
+    BallVelcro,
+    Batarang,
+    /// Disruptor.
+    BoostOverride,
+    /// Boot.
+    CarSpring,
+    /// Magnetizer.
+    GravityWell,
+    /// Power Hitter.
+    StrongHit,
+    Swapper,
+    Tornado,
+    HauntedBallBeam,
+    Other,
+}
+
+impl PowerUp {
+    /// Returns None for objects that are not special pickups.
+    pub fn from_object_name(object_name: &str) -> Option<Self> {
+        let name = object_name.strip_prefix(SPECIAL_PICKUP_PREFIX)?;
+        Some(match name {
+            "BallFreeze" => PowerUp::BallFreeze,
+            "GrapplingHook" | "BallGrapplingHook" => PowerUp::GrapplingHook,
+            "BallLasso" => PowerUp::BallLasso,
+            "BallSpring" => PowerUp::BallSpring,
+            "BallVelcro" => PowerUp::BallVelcro,
+            "Batarang" => PowerUp::Batarang,
+            "BoostOverride" => PowerUp::BoostOverride,
+            "CarSpring" => PowerUp::CarSpring,
+            "GravityWell" => PowerUp::GravityWell,
+            "StrongHit" => PowerUp::StrongHit,
+            "Swapper" => PowerUp::Swapper,
+            "Tornado" => PowerUp::Tornado,
+            "HauntedBallBeam" => PowerUp::HauntedBallBeam,
+            _ => PowerUp::Other,
+        })
+    }
+
+    /// Value written to the `held_powerup` column.
+    pub fn to_column_value(self) -> u8 {
+        match self {
+            PowerUp::BallFreeze => 0,
+            PowerUp::GrapplingHook => 1,
+            PowerUp::BallLasso => 2,
+            PowerUp::BallSpring => 3,
+            PowerUp::BallVelcro => 4,
+            PowerUp::Batarang => 5,
+            PowerUp::BoostOverride => 6,
+            PowerUp::CarSpring => 7,
+            PowerUp::GravityWell => 8,
+            PowerUp::StrongHit => 9,
+            PowerUp::Swapper => 10,
+            PowerUp::Tornado => 11,
+            PowerUp::HauntedBallBeam => 12,
+            PowerUp::Other => 255,
+        }
+    }
+}
+
+/// Handles a single `SpecialPickup_*` car component.
+/// The item is held from when the component is attached to a player's car until it is activated.
+#[derive(Debug, Clone)]
+pub struct PowerUpHandler<'a> {
+    frame_parser: &'a FrameParser,
+    power_up: PowerUp,
+    holder_wrapped_unique_id: Option<WrappedUniqueId>,
+    is_used: bool,
+}
+
+impl<'a> PowerUpHandler<'a> {
+    pub fn for_power_up(frame_parser: &'a FrameParser, power_up: PowerUp) -> Self {
+        Self {
+            frame_parser,
+            power_up,
+            holder_wrapped_unique_id: None,
+            is_used: false,
+        }
+    }
+}
+
+impl<'a> ActorHandler<'a> for PowerUpHandler<'a> {
+    fn new(frame_parser: &'a FrameParser) -> Self {
+        Self::for_power_up(frame_parser, PowerUp::Other)
+    }
+
+    fn update(
+        &mut self,
+        actor: &Actor,
+        frame_number: usize,
+        time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
+        let attributes = actor.attributes.borrow();
+        let player_wrapped_unique_id =
+            match get_car_component_player_wrapped_unique_id(self.frame_parser, &attributes)? {
+                Some(player_wrapped_unique_id) => player_wrapped_unique_id,
+                None => return Ok(()),
+            };
+
+        if self.holder_wrapped_unique_id.as_ref() != Some(&player_wrapped_unique_id) {
+            self.holder_wrapped_unique_id = Some(player_wrapped_unique_id.clone());
+            self.is_used = false;
+            self.frame_parser
+                .power_up_events_data
+                .borrow_mut()
+                .push(PowerUpEventData {
+                    frame_number,
+                    time,
+                    player_wrapped_unique_id: player_wrapped_unique_id.clone(),
+                    power_up: self.power_up,
+                    kind: PowerUpEventKind::Received,
+                    target_wrapped_unique_id: None,
+                });
+        }
+        if self.is_used {
+            return Ok(());
+        }
+
+        if get_car_component_is_active(&attributes) == Some(true) {
+            self.is_used = true;
+            let mut target_wrapped_unique_id = None;
+            if let Some(Attribute::ActiveActor(active_actor)) =
+                attributes.get(AttributeKey::SpecialPickupTargeted)
+            {
+                let car_ids_to_player_ids = self.frame_parser.car_ids_to_player_ids.borrow();
+                if let Some(target_player_actor_id) = car_ids_to_player_ids.get(&active_actor.actor)
+                {
+                    target_wrapped_unique_id = self
+                        .frame_parser
+                        .players_wrapped_unique_id
+                        .borrow()
+                        .get(target_player_actor_id)
+                        .cloned();
+                }
+            }
+            self.frame_parser
+                .power_up_events_data
+                .borrow_mut()
+                .push(PowerUpEventData {
+                    frame_number,
+                    time,
+                    player_wrapped_unique_id,
+                    power_up: self.power_up,
+                    kind: PowerUpEventKind::Used,
+                    target_wrapped_unique_id,
+                });
+        } else {
+            self.frame_parser
+                .current_frame_snapshot
+                .borrow_mut()
+                .players_power_up_data
+                .insert(
+                    player_wrapped_unique_id,
+                    TimeSeriesPowerUpData {
+                        held_powerup: Some(self.power_up.to_column_value()),
+                    },
+                );
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PowerUpEventKind {
+    Received,
+    Used,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerUpEventData {
+    pub frame_number: usize,
+    pub time: f32,
+    pub player_wrapped_unique_id: WrappedUniqueId,
+    pub power_up: PowerUp,
+    pub kind: PowerUpEventKind,
+    /// Player whose car was targeted, for targeted items (where replicated).
+    pub target_wrapped_unique_id: Option<WrappedUniqueId>,
+}
+
+time_series_columns! {
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub struct TimeSeriesPowerUpData => TimeSeriesPowerUpColumns {
+        pub held_powerup: Option<u8>,
+    }
+}
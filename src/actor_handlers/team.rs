@@ -1,7 +1,6 @@
-use crate::actor_handlers::ActorHandler;
+use crate::actor_handlers::{ActorAttributes, ActorHandler, ActorHandlerError, AttributeKey};
 use crate::frame_parser::{Actor, FrameParser};
 use boxcars::Attribute;
-use std::collections::HashMap;
 
 #[derive(Debug, Clone)]
 pub struct BlueTeamHandler<'a> {
@@ -13,11 +12,18 @@ impl<'a> ActorHandler<'a> for BlueTeamHandler<'a> {
         Self { frame_parser }
     }
 
-    fn update(&mut self, actor: &Actor, _frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         let actor_id = actor.new_actor.actor_id;
         let attributes = actor.attributes.borrow();
         let mut teams_actor = self.frame_parser.teams_data.borrow_mut();
         teams_actor.insert(actor_id, TeamData::from(false, &attributes));
+        Ok(())
     }
 }
 
@@ -31,28 +37,71 @@ impl<'a> ActorHandler<'a> for OrangeTeamHandler<'a> {
         Self { frame_parser }
     }
 
-    fn update(&mut self, actor: &Actor, _frame_number: usize, _time: f32, _delta: f32) {
+    fn update(
+        &mut self,
+        actor: &Actor,
+        _frame_number: usize,
+        _time: f32,
+        _delta: f32,
+    ) -> Result<(), ActorHandlerError> {
         let actor_id = actor.new_actor.actor_id;
         let attributes = actor.attributes.borrow();
         let mut teams_actor = self.frame_parser.teams_data.borrow_mut();
         teams_actor.insert(actor_id, TeamData::from(true, &attributes));
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TeamData {
     pub is_orange: bool,
     pub score: i32,
+    /// Custom team name, as set in private and tournament matches.
+    pub name: Option<String>,
+    pub club_id: Option<u64>,
+    /// Club colours, as indices into the game's colour palette.
+    pub primary_color: Option<u8>,
+    pub accent_color: Option<u8>,
 }
 
 impl TeamData {
-    pub fn from(is_orange: bool, attributes: &HashMap<String, boxcars::Attribute>) -> Self {
+    pub fn from(is_orange: bool, attributes: &ActorAttributes) -> Self {
+        let mut name = None;
+        if let Some(Attribute::String(_name)) = attributes
+            .get(AttributeKey::CustomTeamName)
+            .or_else(|| attributes.get(AttributeKey::TeamName))
+        {
+            if !_name.is_empty() {
+                name = Some(_name.to_string());
+            }
+        }
+        let mut club_id = None;
+        if let Some(Attribute::QWord(_club_id)) = attributes.get(AttributeKey::ClubId) {
+            // Teams that are not clubs replicate a club id of 0.
+            if *_club_id != 0 {
+                club_id = Some(*_club_id);
+            }
+        }
+        let mut primary_color = None;
+        let mut accent_color = None;
+        if let Some(Attribute::ClubColors(club_colors)) = attributes.get(AttributeKey::ClubColors) {
+            if club_colors.blue_flag {
+                primary_color = Some(club_colors.blue_color);
+            }
+            if club_colors.orange_flag {
+                accent_color = Some(club_colors.orange_color);
+            }
+        }
         Self {
             is_orange,
-            score: match attributes.get("Engine.TeamInfo:Score") {
+            score: match attributes.get(AttributeKey::TeamScore) {
                 Some(Attribute::Int(score)) => *score,
                 _ => 0,
             },
+            name,
+            club_id,
+            primary_color,
+            accent_color,
         }
     }
 }
@@ -1,9 +1,10 @@
+use crate::actor_handlers::{ActorAttributes, ActorHandlerError, AttributeKey};
 use crate::frame_parser::Actor;
 use boxcars::attributes::{RemoteId, UniqueId};
-use boxcars::Attribute;
+use boxcars::{ActorId, Attribute};
 use log::warn;
 use serde::{Serialize, Serializer};
-use std::collections::{hash_map::DefaultHasher, HashMap};
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
 use std::hash::{Hash, Hasher};
 
@@ -27,17 +28,17 @@ pub struct RigidBodyData {
 impl RigidBodyData {
     pub fn from(
         actor: &Actor,
-        attributes: &HashMap<String, boxcars::Attribute>,
+        attributes: &ActorAttributes,
         replay_version: i32,
-    ) -> Self {
+    ) -> Result<Self, ActorHandlerError> {
         if replay_version < 2 {
-            panic!("Cannot parse replay version < 2")
+            return Err(ActorHandlerError::UnsupportedReplayVersion(replay_version));
         }
         let initial_location = actor
             .new_actor
             .initial_trajectory
             .location
-            .expect("RB actor has no initial location.");
+            .ok_or(ActorHandlerError::MissingInitialLocation)?;
 
         let mut is_sleeping = None;
         let mut pos_x = Some(initial_location.x as f32);
@@ -63,9 +64,7 @@ impl RigidBodyData {
         let mut ang_vel_y = None;
         let mut ang_vel_z = None;
 
-        if let Some(Attribute::RigidBody(rb_state)) =
-            attributes.get("TAGame.RBActor_TA:ReplicatedRBState")
-        {
+        if let Some(Attribute::RigidBody(rb_state)) = attributes.get(AttributeKey::RigidBodyState) {
             is_sleeping = Some(rb_state.sleeping);
 
             let location = rb_state.location;
@@ -120,7 +119,7 @@ impl RigidBodyData {
             }
         }
 
-        Self {
+        Ok(Self {
             is_sleeping,
             pos_x,
             pos_y,
@@ -135,7 +134,7 @@ impl RigidBodyData {
             ang_vel_x,
             ang_vel_y,
             ang_vel_z,
-        }
+        })
     }
 }
 
@@ -157,24 +156,143 @@ fn rotator_to_quat(pitch: f32, yaw: f32, roll: f32) -> (f32, f32, f32, f32) {
     (w / norm, x / norm, y / norm, z / norm)
 }
 
+static BOT_NAMES: [&str; 54] = [
+    "Armstrong",
+    "Bandit",
+    "Beast",
+    "Boomer",
+    "Buzz",
+    "C-Block",
+    "Casper",
+    "Caveman",
+    "Centice",
+    "Chipper",
+    "Cougar",
+    "Dude",
+    "Foamer",
+    "Fury",
+    "Gerwin",
+    "Goose",
+    "Heater",
+    "Hollywood",
+    "Hound",
+    "Iceman",
+    "Imp",
+    "Jester",
+    "Junker",
+    "Khan",
+    "Marley",
+    "Maverick",
+    "Merlin",
+    "Middy",
+    "Mountain",
+    "Myrtle",
+    "Outlaw",
+    "Poncho",
+    "Rainmaker",
+    "Raja",
+    "Rex",
+    "Roundhouse",
+    "Sabretooth",
+    "Saltie",
+    "Samara",
+    "Scout",
+    "Shepard",
+    "Slider",
+    "Squall",
+    "Sticks",
+    "Stinger",
+    "Storm",
+    "Sultan",
+    "Sundown",
+    "Swabbie",
+    "Tex",
+    "Tusk",
+    "Viper",
+    "Wolfman",
+    "Yuri",
+];
+
+/// Identifies a player across PRI actors.
+/// Bots, which have no unique id or share the `SplitScreen(0)` placeholder, get a synthetic id from their name and PRI actor id,
+/// so that bots with the same name are kept apart.
 #[derive(Debug, Clone)]
-pub struct WrappedUniqueId(UniqueId);
+pub enum WrappedUniqueId {
+    Online(UniqueId),
+    Synthetic(String, ActorId),
+}
 
 impl WrappedUniqueId {
-    pub fn from(attributes: &HashMap<String, Attribute>) -> Self {
-        if let Some(Attribute::UniqueId(unique_id)) =
-            attributes.get("Engine.PlayerReplicationInfo:UniqueId")
-        {
-            Self(unique_id.as_ref().clone())
-        } else {
-            panic!("Could not get UniqueId attribute.")
+    /// Errors with `MissingUniqueId` until the id (or a bot's name) is replicated,
+    /// so that players whose name replicates first are not given a synthetic id.
+    pub fn from(
+        actor_id: ActorId,
+        attributes: &ActorAttributes,
+    ) -> Result<Self, ActorHandlerError> {
+        let is_synthetic = match attributes.get(AttributeKey::UniqueId) {
+            Some(Attribute::UniqueId(unique_id)) if !has_placeholder_unique_id(attributes) => {
+                return Ok(WrappedUniqueId::Online(unique_id.as_ref().clone()));
+            }
+            Some(Attribute::UniqueId(_)) => true,
+            _ => matches!(
+                attributes.get(AttributeKey::IsBot),
+                Some(Attribute::Boolean(true))
+            ),
+        };
+        match attributes.get(AttributeKey::PlayerName) {
+            Some(Attribute::String(name)) if is_synthetic => {
+                Ok(WrappedUniqueId::Synthetic(name.to_string(), actor_id))
+            }
+            _ => Err(ActorHandlerError::MissingUniqueId),
+        }
+    }
+
+    /// The unique id of the player's party leader, from `TAGame.PRI_TA:PartyLeader`.
+    pub fn from_party_leader(attributes: &ActorAttributes) -> Option<Self> {
+        match attributes.get(AttributeKey::PartyLeader) {
+            Some(Attribute::PartyLeader(Some(party_leader)))
+                if party_leader.remote_id != RemoteId::SplitScreen(0) =>
+            {
+                Some(WrappedUniqueId::Online(party_leader.as_ref().clone()))
+            }
+            _ => None,
         }
     }
 }
 
+/// Whether the PRI's unique id is the `SplitScreen(0)` placeholder shared by bots.
+fn has_placeholder_unique_id(attributes: &ActorAttributes) -> bool {
+    match attributes.get(AttributeKey::UniqueId) {
+        Some(Attribute::UniqueId(unique_id)) => unique_id.remote_id == RemoteId::SplitScreen(0),
+        _ => false,
+    }
+}
+
+/// Detects bots from `Engine.PlayerReplicationInfo:bBot`, falling back to bot names for PRIs with the placeholder unique id.
+pub fn is_bot(attributes: &ActorAttributes) -> bool {
+    if let Some(Attribute::Boolean(_is_bot)) = attributes.get(AttributeKey::IsBot) {
+        return *_is_bot;
+    }
+    match attributes.get(AttributeKey::PlayerName) {
+        Some(Attribute::String(name)) => {
+            has_placeholder_unique_id(attributes) && BOT_NAMES.contains(&name.as_str())
+        }
+        _ => false,
+    }
+}
+
 impl Hash for WrappedUniqueId {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        match &self.0.remote_id {
+        let unique_id = match self {
+            WrappedUniqueId::Online(unique_id) => unique_id,
+            WrappedUniqueId::Synthetic(name, actor_id) => {
+                "Synthetic".hash(state);
+                name.hash(state);
+                actor_id.hash(state);
+                return;
+            }
+        };
+        match &unique_id.remote_id {
             RemoteId::PlayStation(ps4_id) => {
                 "PlayStation".hash(state);
                 ps4_id.online_id.hash(state);
@@ -214,8 +332,17 @@ impl Hash for WrappedUniqueId {
 
 impl PartialEq for WrappedUniqueId {
     fn eq(&self, other: &WrappedUniqueId) -> bool {
-        // TODO: Replace with accurate impl (referencing hash impl).
-        self.0.remote_id == other.0.remote_id
+        match (self, other) {
+            // TODO: Replace with accurate impl (referencing hash impl).
+            (WrappedUniqueId::Online(unique_id), WrappedUniqueId::Online(other_unique_id)) => {
+                unique_id.remote_id == other_unique_id.remote_id
+            }
+            (
+                WrappedUniqueId::Synthetic(name, actor_id),
+                WrappedUniqueId::Synthetic(other_name, other_actor_id),
+            ) => name == other_name && actor_id == other_actor_id,
+            _ => false,
+        }
     }
 }
 impl Eq for WrappedUniqueId {}
@@ -224,7 +351,7 @@ impl fmt::Display for WrappedUniqueId {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut hasher = DefaultHasher::new();
         self.hash(&mut hasher);
-        write!(f, "{}", hasher.finish().to_string())
+        write!(f, "{}", hasher.finish())
     }
 }
 
@@ -236,3 +363,114 @@ impl Serialize for WrappedUniqueId {
         serializer.serialize_str(&self.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor_handlers::ReplayObjectIds;
+    use boxcars::attributes::Ps4Id;
+    use std::sync::Arc;
+
+    fn attributes(values: Vec<(AttributeKey, Attribute)>) -> ActorAttributes {
+        let replay_objects: Vec<String> = AttributeKey::ALL
+            .iter()
+            .map(|key| key.object_name().to_string())
+            .collect();
+        let object_ids = Arc::new(ReplayObjectIds::new(&replay_objects));
+        let mut attributes = ActorAttributes::new(object_ids.clone());
+        for (key, value) in values {
+            attributes.insert(object_ids.get(key).unwrap(), value);
+        }
+        attributes
+    }
+
+    fn unique_id(remote_id: RemoteId) -> Attribute {
+        Attribute::UniqueId(Box::new(UniqueId {
+            system_id: 1,
+            remote_id,
+            local_id: 0,
+        }))
+    }
+
+    fn name(name: &str) -> Attribute {
+        Attribute::String(name.to_string())
+    }
+
+    #[test]
+    fn online_players_use_unique_id() {
+        let remote_id = RemoteId::Steam(76561198000000000);
+        let attributes = attributes(vec![
+            (AttributeKey::UniqueId, unique_id(remote_id.clone())),
+            (AttributeKey::PlayerName, name("Player")),
+        ]);
+        match WrappedUniqueId::from(ActorId(1), &attributes) {
+            Ok(WrappedUniqueId::Online(unique_id)) => assert_eq!(unique_id.remote_id, remote_id),
+            wrapped_unique_id => panic!("unexpected {:?}", wrapped_unique_id),
+        }
+        assert!(!is_bot(&attributes));
+    }
+
+    #[test]
+    fn name_without_unique_id_is_not_latched() {
+        let attributes = attributes(vec![(AttributeKey::PlayerName, name("Player"))]);
+        assert_eq!(
+            WrappedUniqueId::from(ActorId(1), &attributes),
+            Err(ActorHandlerError::MissingUniqueId)
+        );
+        assert_eq!(
+            WrappedUniqueId::from(ActorId(1), &self::attributes(vec![])),
+            Err(ActorHandlerError::MissingUniqueId)
+        );
+    }
+
+    #[test]
+    fn bots_use_synthetic_ids() {
+        let placeholder_attributes = attributes(vec![
+            (AttributeKey::UniqueId, unique_id(RemoteId::SplitScreen(0))),
+            (AttributeKey::PlayerName, name("Casper")),
+        ]);
+        assert_eq!(
+            WrappedUniqueId::from(ActorId(1), &placeholder_attributes),
+            Ok(WrappedUniqueId::Synthetic("Casper".to_string(), ActorId(1)))
+        );
+        assert!(is_bot(&placeholder_attributes));
+        // Bots with the same name on different PRIs are kept apart.
+        assert_ne!(
+            WrappedUniqueId::from(ActorId(1), &placeholder_attributes),
+            WrappedUniqueId::from(ActorId(2), &placeholder_attributes)
+        );
+
+        let is_bot_attributes = attributes(vec![
+            (AttributeKey::IsBot, Attribute::Boolean(true)),
+            (AttributeKey::PlayerName, name("Custom")),
+        ]);
+        assert_eq!(
+            WrappedUniqueId::from(ActorId(1), &is_bot_attributes),
+            Ok(WrappedUniqueId::Synthetic("Custom".to_string(), ActorId(1)))
+        );
+        assert!(is_bot(&is_bot_attributes));
+    }
+
+    #[test]
+    fn is_bot_prefers_is_bot_attribute() {
+        let attributes = attributes(vec![
+            (AttributeKey::IsBot, Attribute::Boolean(false)),
+            (AttributeKey::UniqueId, unique_id(RemoteId::SplitScreen(0))),
+            (AttributeKey::PlayerName, name("Casper")),
+        ]);
+        assert!(!is_bot(&attributes));
+        // Bot names are only trusted for PRIs with the placeholder unique id.
+        let online_attributes = self::attributes(vec![
+            (
+                AttributeKey::UniqueId,
+                unique_id(RemoteId::PlayStation(Ps4Id {
+                    online_id: 1,
+                    name: "Casper".to_string(),
+                    unknown1: vec![],
+                })),
+            ),
+            (AttributeKey::PlayerName, name("Casper")),
+        ]);
+        assert!(!is_bot(&online_attributes));
+    }
+}
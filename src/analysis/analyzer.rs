@@ -1,7 +1,7 @@
 use crate::analysis::GameplayPeriod;
 use crate::analysis::{Hit, HitDetectionError, Stats, StatsGenerationError};
+use crate::frame_parser::ParsedReplay;
 use crate::outputs::{DataFramesOutput, MetadataOutput};
-use crate::CarballParser;
 use serde::Serialize;
 use std::fs::File;
 use std::path::PathBuf;
@@ -16,11 +16,11 @@ pub struct CarballAnalyzer {
 
 impl CarballAnalyzer {
     pub fn analyze(
-        carball_parser: &CarballParser,
+        parsed_replay: &ParsedReplay,
         metadata: &MetadataOutput,
         data_frames: &DataFramesOutput,
     ) -> Result<Self, CarballAnalyzerError> {
-        let hits = Hit::find_hits(&carball_parser.frame_parser, metadata)
+        let hits = Hit::find_hits(parsed_replay, metadata)
             .map_err(CarballAnalyzerError::HitDetectionError)?;
 
         let gameplay_periods = GameplayPeriod::get_periods(metadata, data_frames);
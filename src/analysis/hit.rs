@@ -1,6 +1,6 @@
 use crate::actor_handlers::{TimeSeriesBallData, TimeSeriesCarData, WrappedUniqueId};
 use crate::analysis::{predict_ball_bounce, BallPredictionError};
-use crate::frame_parser::FrameParser;
+use crate::frame_parser::ParsedReplay;
 use crate::outputs::MetadataOutput;
 use log::{error, warn};
 use serde::Serialize;
@@ -35,12 +35,12 @@ pub struct HitDebugInfo {
 
 impl Hit {
     pub fn find_hits(
-        frame_parser: &FrameParser,
+        parsed_replay: &ParsedReplay,
         metadata: &MetadataOutput,
     ) -> Result<Vec<Hit>, HitDetectionError> {
-        let time_series_replay_data = frame_parser.time_series_replay_data.borrow();
-        let time_series_ball_data = frame_parser.time_series_ball_data.borrow();
-        let players_time_series_car_data = frame_parser.players_time_series_car_data.borrow();
+        let time_series_replay_data = &parsed_replay.time_series_replay_data;
+        let time_series_ball_data = &parsed_replay.time_series_ball_data;
+        let players_time_series_car_data = &parsed_replay.players_time_series_car_data;
 
         let mut blue_players_wrapped_unique_id = vec![];
         let mut orange_players_wrapped_unique_id = vec![];
@@ -54,9 +54,9 @@ impl Hit {
         }
 
         let mut hits = vec![];
-        let mut previous_frame_ball_data: Option<&TimeSeriesBallData> = None;
-        for frame_number in 0..(frame_parser.frame_count - 1) {
-            if let Some(ball_data) = time_series_ball_data.get(&frame_number) {
+        let mut previous_frame_ball_data: Option<TimeSeriesBallData> = None;
+        for frame_number in 0..(parsed_replay.frame_count - 1) {
+            if let Some(ball_data) = time_series_ball_data.get(frame_number) {
                 match previous_frame_ball_data {
                     None => {
                         previous_frame_ball_data = Some(ball_data);
@@ -65,7 +65,7 @@ impl Hit {
                     Some(previous_frame_ball_data_value) => {
                         if let Some(hit_team_num) = ball_data.hit_team_num {
                             if let Some((ang_vel_x, ang_vel_y, ang_vel_z)) =
-                                unwrap_ang_vel(ball_data)
+                                unwrap_ang_vel(&ball_data)
                             {
                                 // Detect hits
                                 let mut hit_team_num_changed = false;
@@ -94,11 +94,11 @@ impl Hit {
                                     ang_vel_changed = true;
                                 }
                                 let delta = time_series_replay_data
-                                    .get(&frame_number)
+                                    .get(frame_number)
                                     .ok_or(HitDetectionError::MissingDelta(frame_number))?
                                     .delta;
                                 {
-                                    if predict_ball_bounce(previous_frame_ball_data_value, delta)
+                                    if predict_ball_bounce(&previous_frame_ball_data_value, delta)
                                         .map_err(HitDetectionError::BallPredictionError)?
                                     {
                                         predicted_bounce = true;
@@ -107,8 +107,8 @@ impl Hit {
 
                                 if let Some(_previous_frame_ball_data) = previous_frame_ball_data {
                                     let previous_ball_speed =
-                                        get_ball_speed(_previous_frame_ball_data);
-                                    let current_ball_speed = get_ball_speed(ball_data);
+                                        get_ball_speed(&_previous_frame_ball_data);
+                                    let current_ball_speed = get_ball_speed(&ball_data);
                                     if let Some(_current_ball_speed) = current_ball_speed {
                                         if let Some(_previous_ball_speed) = previous_ball_speed {
                                             if _current_ball_speed
@@ -160,7 +160,7 @@ impl Hit {
 
                                         let potential_hit_player_previous_frame_datas: HashMap<
                                             WrappedUniqueId,
-                                            Option<&TimeSeriesCarData>,
+                                            Option<TimeSeriesCarData>,
                                         > = potential_hit_players
                                             .iter()
                                             .map(|wrapped_unique_id| {
@@ -168,17 +168,16 @@ impl Hit {
                                                     wrapped_unique_id.clone(),
                                                     players_time_series_car_data
                                                         .get(wrapped_unique_id)
-                                                        .map(|time_series_car_data| {
+                                                        .and_then(|time_series_car_data| {
                                                             time_series_car_data
-                                                                .get(&(frame_number - 1))
-                                                        })
-                                                        .flatten(),
+                                                                .get(frame_number - 1)
+                                                        }),
                                                 )
                                             })
                                             .collect();
 
                                         let player_distances = get_player_distances(
-                                            previous_frame_ball_data_value,
+                                            &previous_frame_ball_data_value,
                                             potential_hit_player_previous_frame_datas,
                                         );
                                         let nearest_player_and_distance =
@@ -245,7 +244,7 @@ impl Hit {
 
 fn get_player_distances(
     ball_data: &TimeSeriesBallData,
-    player_datas: HashMap<WrappedUniqueId, Option<&TimeSeriesCarData>>,
+    player_datas: HashMap<WrappedUniqueId, Option<TimeSeriesCarData>>,
 ) -> HashMap<WrappedUniqueId, f32> {
     let mut player_distances = HashMap::with_capacity(player_datas.len());
     for (wrapped_unique_id, player_data) in player_datas.iter() {
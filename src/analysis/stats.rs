@@ -1,6 +1,7 @@
 use crate::actor_handlers::WrappedUniqueId;
 use crate::analysis::GameplayPeriod;
-use crate::outputs::{DataFramesOutput, MetadataOutput, Player};
+use crate::cleaner::{boost_pad_kind, Arena, BoostPickupKind, BOOST_PADS_COORDS};
+use crate::outputs::{DataFramesOutput, MetadataOutput, Party, Player};
 use log::warn;
 use polars::error::PolarsError;
 use polars::prelude::{
@@ -12,11 +13,12 @@ use std::collections::{HashMap, HashSet};
 use thiserror::Error;
 
 static BOOST_PER_SECOND: f32 = 85.0 / 2.55;
-static PITCH_Y_THIRD_THRESHOLD: f32 = 10240.0 / 3.0 / 2.0;
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Stats {
     pub players: HashMap<WrappedUniqueId, PlayerStats>,
+    /// Only available with replicated pickups on arenas with the standard pad layout.
+    pub boost_pads: Option<BoostPadStats>,
 }
 
 impl Stats {
@@ -43,6 +45,7 @@ impl Stats {
             .filter(&gameplay_frames_boolean_mask)
             .unwrap();
 
+        let arena = metadata.game.game_mode.arena();
         let mut players_stats: HashMap<WrappedUniqueId, PlayerStats> = HashMap::new();
         for player in metadata.players.iter() {
             if let Some(player_df) = data_frames.players.get(&player.unique_id) {
@@ -50,6 +53,8 @@ impl Stats {
                     player,
                     &player_df.filter(&gameplay_frames_boolean_mask).unwrap(),
                     &game_df,
+                    &arena,
+                    Party::party_size(&metadata.parties, &player.unique_id),
                 )
                 .map_err(StatsGenerationError::PlayerStatsError)?;
                 players_stats.insert(player.unique_id.clone(), player_stats);
@@ -62,14 +67,28 @@ impl Stats {
                 )
             }
         }
+        let boost_pads = BoostPadStats::from(
+            metadata,
+            &data_frames
+                .boost_pads
+                .filter(&gameplay_frames_boolean_mask)
+                .map_err(StatsGenerationError::BoostPadStatsError)?,
+            &game_df,
+            &gameplay_frames_set,
+        )
+        .map_err(StatsGenerationError::BoostPadStatsError)?;
         Ok(Self {
             players: players_stats,
+            boost_pads,
         })
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub struct PlayerStats {
+    /// Number of players in the match who queued with the player (1 if solo-queued).
+    pub party_size: usize,
+
     // Boost
     pub big_pads_collected: u32,
     pub small_pads_collected: u32,
@@ -104,7 +123,12 @@ impl PlayerStats {
         player: &Player,
         player_df: &DataFrame,
         game_df: &DataFrame,
+        arena: &Arena,
+        party_size: usize,
     ) -> Result<Self, PolarsError> {
+        // Thirds of the pitch's length (excluding goals).
+        let pitch_y_third_threshold = arena.back_wall * 2.0 / 3.0 / 2.0;
+
         let boost_pickup = player_df
             .column("boost_pickup")?
             .u8()?
@@ -126,15 +150,15 @@ impl PlayerStats {
         let time_in_blue_half = game_delta.filter(&pos_y.lt(0.0))?.sum().unwrap();
         let time_in_orange_half = game_delta.filter(&pos_y.gt(0.0))?.sum().unwrap();
         let time_in_blue_third = game_delta
-            .filter(&pos_y.lt(-PITCH_Y_THIRD_THRESHOLD))?
+            .filter(&pos_y.lt(-pitch_y_third_threshold))?
             .sum()
             .unwrap();
         let time_in_neutral_third = game_delta
-            .filter(&pos_y.apply(f32::abs).lt(PITCH_Y_THIRD_THRESHOLD))?
+            .filter(&pos_y.apply(f32::abs).lt(pitch_y_third_threshold))?
             .sum()
             .unwrap();
         let time_in_orange_third = game_delta
-            .filter(&pos_y.gt(PITCH_Y_THIRD_THRESHOLD))?
+            .filter(&pos_y.gt(pitch_y_third_threshold))?
             .sum()
             .unwrap();
 
@@ -158,6 +182,8 @@ impl PlayerStats {
         }
 
         Ok(Self {
+            party_size,
+
             big_pads_collected: boost_pickup.eq(2).sum().unwrap(),
             small_pads_collected: boost_pickup.eq(1).sum().unwrap(),
             boost_used: game_delta
@@ -187,8 +213,96 @@ impl PlayerStats {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct BoostPadStats {
+    /// Share of big pad pickups taken by each team.
+    pub blue_big_pads_share: f32,
+    pub orange_big_pads_share: f32,
+    /// Average fraction of gameplay time that pads were respawning.
+    pub average_big_pad_downtime: f32,
+    pub average_small_pad_downtime: f32,
+    /// Pads taken in the opponent's half.
+    pub blue_opponent_side_pads: u32,
+    pub orange_opponent_side_pads: u32,
+}
+
+impl BoostPadStats {
+    pub fn from(
+        metadata: &MetadataOutput,
+        boost_pads_df: &DataFrame,
+        game_df: &DataFrame,
+        gameplay_frames_set: &HashSet<i32>,
+    ) -> Result<Option<Self>, PolarsError> {
+        let players_is_orange: HashMap<&WrappedUniqueId, bool> = metadata
+            .players
+            .iter()
+            .filter_map(|player| Some((&player.unique_id, player.is_orange?)))
+            .collect();
+
+        let mut big_pads_collected: HashMap<bool, u32> = HashMap::new();
+        let mut opponent_side_pads: HashMap<bool, u32> = HashMap::new();
+        let mut has_pad_pickups = false;
+        for boost_pickup in metadata.boost_pickups.iter() {
+            if !gameplay_frames_set.contains(&(boost_pickup.frame_number as i32)) {
+                continue;
+            }
+            let pad_index = match boost_pickup.pad_index {
+                Some(pad_index) => pad_index,
+                None => continue,
+            };
+            has_pad_pickups = true;
+            let is_orange = match players_is_orange.get(&boost_pickup.unique_id) {
+                Some(is_orange) => *is_orange,
+                None => continue,
+            };
+            if boost_pad_kind(pad_index) == BoostPickupKind::Full {
+                *big_pads_collected.entry(is_orange).or_insert(0) += 1;
+            }
+            let pad_y = BOOST_PADS_COORDS[pad_index][1];
+            if (is_orange && pad_y < 0.0) || (!is_orange && pad_y > 0.0) {
+                *opponent_side_pads.entry(is_orange).or_insert(0) += 1;
+            }
+        }
+        if !has_pad_pickups {
+            return Ok(None);
+        }
+
+        let game_delta = game_df.column("delta")?.f32()?;
+        let total_game_delta = game_delta.sum().unwrap();
+        let mut big_pads_downtime = vec![];
+        let mut small_pads_downtime = vec![];
+        for pad_index in 0..BOOST_PADS_COORDS.len() {
+            let is_available = boost_pads_df
+                .column(&format!("pad_{}_is_available", pad_index))?
+                .u8()?;
+            let downtime =
+                game_delta.filter(&is_available.eq(0))?.sum().unwrap_or(0.0) / total_game_delta;
+            match boost_pad_kind(pad_index) {
+                BoostPickupKind::Full => big_pads_downtime.push(downtime),
+                BoostPickupKind::Small => small_pads_downtime.push(downtime),
+            }
+        }
+
+        let blue_big_pads = *big_pads_collected.get(&false).unwrap_or(&0);
+        let orange_big_pads = *big_pads_collected.get(&true).unwrap_or(&0);
+        let total_big_pads = (blue_big_pads + orange_big_pads).max(1) as f32;
+        Ok(Some(Self {
+            blue_big_pads_share: blue_big_pads as f32 / total_big_pads,
+            orange_big_pads_share: orange_big_pads as f32 / total_big_pads,
+            average_big_pad_downtime: big_pads_downtime.iter().sum::<f32>()
+                / big_pads_downtime.len() as f32,
+            average_small_pad_downtime: small_pads_downtime.iter().sum::<f32>()
+                / small_pads_downtime.len() as f32,
+            blue_opponent_side_pads: *opponent_side_pads.get(&false).unwrap_or(&0),
+            orange_opponent_side_pads: *opponent_side_pads.get(&true).unwrap_or(&0),
+        }))
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum StatsGenerationError {
     #[error("Player stats generation Polars error: {0}")]
     PlayerStatsError(PolarsError),
+    #[error("Boost pad stats generation Polars error: {0}")]
+    BoostPadStatsError(PolarsError),
 }
@@ -0,0 +1,433 @@
+use crate::analysis::{CarballAnalyzer, CarballAnalyzerError, CarballAnalyzerWriteError};
+use crate::outputs::{
+    DataFrameOutputFormat, DataFramesOutput, MetadataOutput, OutputError, ParseOutputWriter,
+    ParseOutputWriterError, RangeChecker, RangeCheckerError,
+};
+use crate::{parse_header_file, CarballError, CarballParser, ParseOptions};
+use indicatif::ProgressBar;
+use log::{error, info};
+use rayon::prelude::*;
+use serde::Serialize;
+use std::any::Any;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::fs;
+use std::fs::File;
+use std::panic;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Options controlling which outputs are generated and written for each replay.
+#[derive(Debug, Clone)]
+pub struct ProcessOptions {
+    pub parse_options: ParseOptions,
+    pub data_frame_output_format: Option<DataFrameOutputFormat>,
+    pub header_only: bool,
+    pub skip_data_frames: bool,
+    pub skip_write_data_frames: bool,
+    /// Resamples written DataFrames to this rate (in Hz). Range checks and analysis use the original frames.
+    pub resample_rate: Option<f32>,
+    pub skip_checks: bool,
+    pub skip_analysis: bool,
+}
+
+impl Default for ProcessOptions {
+    fn default() -> Self {
+        Self {
+            parse_options: ParseOptions::default(),
+            data_frame_output_format: Some(DataFrameOutputFormat::Parquet),
+            header_only: false,
+            skip_data_frames: false,
+            skip_write_data_frames: false,
+            resample_rate: None,
+            skip_checks: false,
+            skip_analysis: false,
+        }
+    }
+}
+
+/// Parses a replay and writes its outputs into `output_dir`, which is created if needed.
+pub fn process_replay_file(
+    replay_path: &Path,
+    output_dir: &Path,
+    options: &ProcessOptions,
+) -> Result<(), ProcessError> {
+    fs::create_dir_all(output_dir).map_err(ProcessError::CreateOutputDirError)?;
+    let parse_output_writer =
+        ParseOutputWriter::new(output_dir.to_path_buf(), options.data_frame_output_format);
+
+    if options.header_only {
+        let header_output = parse_header_file(replay_path).map_err(ProcessError::ParseError)?;
+        return parse_output_writer
+            .write_header_output(&header_output)
+            .map_err(ProcessError::WriteError);
+    }
+
+    let carball_parser = CarballParser::parse_file_with_options(
+        replay_path.to_path_buf(),
+        options.parse_options.clone(),
+    )
+    .map_err(ProcessError::ParseError)?;
+
+    let metadata =
+        MetadataOutput::generate_from(&carball_parser.replay, &carball_parser.parsed_replay)
+            .map_err(ProcessError::OutputError)?;
+    let data_frames = if options.skip_data_frames {
+        None
+    } else {
+        Some(
+            DataFramesOutput::generate_from(&carball_parser.parsed_replay)
+                .map_err(ProcessError::OutputError)?,
+        )
+    };
+
+    if let Some(_data_frames) = &data_frames {
+        if !options.skip_checks {
+            RangeChecker::for_game_mode(metadata.game.game_mode, metadata.game.ball_type)
+                .check_ranges(_data_frames)
+                .map_err(ProcessError::RangeCheckError)?;
+        }
+    }
+
+    let resampled_data_frames = match (&data_frames, options.resample_rate) {
+        (Some(_data_frames), Some(resample_rate)) if !options.skip_write_data_frames => Some(
+            _data_frames
+                .resample(resample_rate)
+                .map_err(ProcessError::OutputError)?,
+        ),
+        _ => None,
+    };
+    let data_frames_to_write = if options.skip_write_data_frames {
+        None
+    } else {
+        resampled_data_frames.as_ref().or(data_frames.as_ref())
+    };
+    parse_output_writer
+        .write_outputs(Some(&metadata), data_frames_to_write)
+        .map_err(ProcessError::WriteError)?;
+
+    if let Some(_data_frames) = &data_frames {
+        if !options.skip_analysis {
+            let analyzer =
+                CarballAnalyzer::analyze(&carball_parser.parsed_replay, &metadata, _data_frames)
+                    .map_err(ProcessError::AnalysisError)?;
+            analyzer
+                .write(output_dir.to_path_buf())
+                .map_err(ProcessError::WriteAnalysisError)?;
+        }
+    }
+    Ok(())
+}
+
+/// Finds replays to process from either a directory (all `.replay` files directly within it) or a glob pattern.
+pub fn find_replay_files(input: &str) -> Result<Vec<PathBuf>, BatchError> {
+    let input_path = Path::new(input);
+    let mut replay_paths = if input_path.is_dir() {
+        let mut replay_paths = vec![];
+        for entry in fs::read_dir(input_path).map_err(BatchError::ReadDirError)? {
+            let path = entry.map_err(BatchError::ReadDirError)?.path();
+            if path.is_file() && path.extension() == Some(OsStr::new("replay")) {
+                replay_paths.push(path);
+            }
+        }
+        replay_paths
+    } else {
+        glob::glob(input)
+            .map_err(BatchError::GlobPatternError)?
+            .filter_map(|entry| match entry {
+                Ok(path) if path.is_file() => Some(path),
+                Ok(_) => None,
+                Err(err) => {
+                    error!("Failed to read glob entry: {}", err);
+                    None
+                }
+            })
+            .collect()
+    };
+    replay_paths.sort();
+    Ok(replay_paths)
+}
+
+/// Processes replays in parallel, writing each replay's outputs into its own subdirectory of `output_dir`.
+/// Failing (or panicking) replays are recorded in the summary rather than aborting the batch.
+/// The summary is also written to `summary.json` in `output_dir`.
+pub fn process_batch(
+    replay_paths: &[PathBuf],
+    output_dir: &Path,
+    options: &ProcessOptions,
+) -> Result<BatchSummary, BatchError> {
+    let replay_options = ProcessOptions {
+        parse_options: ParseOptions {
+            show_progress: false,
+            ..options.parse_options.clone()
+        },
+        ..options.clone()
+    };
+    process_batch_with(
+        replay_paths,
+        output_dir,
+        options.parse_options.show_progress,
+        |replay_path, replay_output_dir| {
+            process_replay_file(replay_path, replay_output_dir, &replay_options)
+        },
+    )
+}
+
+/// Runs `process_replay` on each replay (with its output subdirectory) in parallel, as `process_batch`.
+fn process_batch_with<F>(
+    replay_paths: &[PathBuf],
+    output_dir: &Path,
+    show_progress: bool,
+    process_replay: F,
+) -> Result<BatchSummary, BatchError>
+where
+    F: Fn(&Path, &Path) -> Result<(), ProcessError> + Sync,
+{
+    fs::create_dir_all(output_dir).map_err(BatchError::CreateOutputDirError)?;
+
+    let progress_bar = if show_progress {
+        Some(ProgressBar::new(replay_paths.len() as u64))
+    } else {
+        None
+    };
+
+    let replay_output_dirs = get_replay_output_dirs(replay_paths, output_dir);
+    let results: Vec<BatchReplayResult> = replay_paths
+        .par_iter()
+        .zip(replay_output_dirs.par_iter())
+        .map(|(replay_path, replay_output_dir)| {
+            let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+                process_replay(replay_path, replay_output_dir)
+            }));
+            let error = match result {
+                Ok(Ok(())) => None,
+                Ok(Err(err)) => Some(err.to_string()),
+                Err(panic_payload) => Some(format!("panicked: {}", panic_message(&*panic_payload))),
+            };
+            if let Some(_error) = &error {
+                error!("Failed to process {:?}: {}", replay_path, _error);
+            }
+            if let Some(_progress_bar) = &progress_bar {
+                _progress_bar.inc(1);
+            }
+            BatchReplayResult {
+                replay_path: replay_path.clone(),
+                output_dir: replay_output_dir.clone(),
+                success: error.is_none(),
+                error,
+            }
+        })
+        .collect();
+    if let Some(_progress_bar) = progress_bar {
+        _progress_bar.finish();
+    }
+
+    let succeeded = results.iter().filter(|result| result.success).count();
+    let summary = BatchSummary {
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    };
+    info!(
+        "Processed {} replays ({} failed)",
+        summary.results.len(),
+        summary.failed
+    );
+
+    let mut summary_path = output_dir.to_path_buf();
+    summary_path.push("summary.json");
+    serde_json::to_writer_pretty(
+        &File::create(summary_path).map_err(BatchError::CreateSummaryFileError)?,
+        &summary,
+    )
+    .map_err(BatchError::WriteSummaryJsonError)?;
+
+    Ok(summary)
+}
+
+/// Names each replay's output subdirectory after the replay's file stem, adding a suffix for duplicate stems.
+fn get_replay_output_dirs(replay_paths: &[PathBuf], output_dir: &Path) -> Vec<PathBuf> {
+    let mut used_names = HashSet::new();
+    replay_paths
+        .iter()
+        .map(|replay_path| {
+            let stem = replay_path.file_stem().map_or_else(
+                || "replay".to_string(),
+                |stem| stem.to_string_lossy().to_string(),
+            );
+            let mut name = stem.clone();
+            let mut suffix = 1;
+            while !used_names.insert(name.clone()) {
+                name = format!("{}_{}", stem, suffix);
+                suffix += 1;
+            }
+            output_dir.join(name)
+        })
+        .collect()
+}
+
+fn panic_message(panic_payload: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic_payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = panic_payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_string()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchSummary {
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BatchReplayResult>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BatchReplayResult {
+    pub replay_path: PathBuf,
+    pub output_dir: PathBuf,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Error, Debug)]
+pub enum ProcessError {
+    #[error("failed to create output directory: {0}")]
+    CreateOutputDirError(std::io::Error),
+    #[error("failed to parse replay: {0}")]
+    ParseError(CarballError),
+    #[error("failed to generate outputs: {0}")]
+    OutputError(OutputError),
+    #[error("failed range checks: {0}")]
+    RangeCheckError(RangeCheckerError),
+    #[error("failed to write outputs: {0}")]
+    WriteError(ParseOutputWriterError),
+    #[error("failed to analyze: {0}")]
+    AnalysisError(CarballAnalyzerError),
+    #[error("failed to write analysis: {0}")]
+    WriteAnalysisError(CarballAnalyzerWriteError),
+}
+
+#[derive(Error, Debug)]
+pub enum BatchError {
+    #[error("failed to read input directory: {0}")]
+    ReadDirError(std::io::Error),
+    #[error("invalid glob pattern: {0}")]
+    GlobPatternError(glob::PatternError),
+    #[error("failed to create output directory: {0}")]
+    CreateOutputDirError(std::io::Error),
+    #[error("failed to create summary file: {0}")]
+    CreateSummaryFileError(std::io::Error),
+    #[error("failed to write summary file to JSON: {0}")]
+    WriteSummaryJsonError(serde_json::Error),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    /// An empty directory under the system's temporary directory, unique to the test.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("carball-batch-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn duplicate_replay_names_get_suffixes() {
+        let replay_paths: Vec<PathBuf> = [
+            "a/game.replay",
+            "b/game.replay",
+            "c/game.replay",
+            "game_1.replay",
+        ]
+        .iter()
+        .map(PathBuf::from)
+        .collect();
+        let output_dirs = get_replay_output_dirs(&replay_paths, Path::new("out"));
+        let names: Vec<&str> = output_dirs
+            .iter()
+            .map(|output_dir| output_dir.file_name().unwrap().to_str().unwrap())
+            .collect();
+        assert_eq!(names, vec!["game", "game_1", "game_2", "game_1_1"]);
+        assert!(output_dirs
+            .iter()
+            .all(|output_dir| output_dir.starts_with("out")));
+    }
+
+    #[test]
+    fn replays_are_found_in_directory_or_by_glob() {
+        let dir = temp_dir("find");
+        for file_name in ["b.replay", "a.replay", "notes.txt"].iter() {
+            File::create(dir.join(file_name)).unwrap();
+        }
+        fs::create_dir_all(dir.join("nested.replay")).unwrap();
+        File::create(dir.join("nested.replay").join("c.replay")).unwrap();
+
+        // Only files directly within the directory, sorted.
+        assert_eq!(
+            find_replay_files(dir.to_str().unwrap()).unwrap(),
+            vec![dir.join("a.replay"), dir.join("b.replay")]
+        );
+        // Directories matching a glob are skipped.
+        assert_eq!(
+            find_replay_files(dir.join("*").join("*.replay").to_str().unwrap()).unwrap(),
+            vec![dir.join("nested.replay").join("c.replay")]
+        );
+        assert_eq!(
+            find_replay_files(dir.join("[ab].*").to_str().unwrap()).unwrap(),
+            vec![dir.join("a.replay"), dir.join("b.replay")]
+        );
+        assert!(matches!(
+            find_replay_files("[").unwrap_err(),
+            BatchError::GlobPatternError(_)
+        ));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn summary_counts_failed_and_panicked_replays() {
+        let dir = temp_dir("summary");
+        let replay_paths: Vec<PathBuf> = ["ok.replay", "error.replay", "panic.replay"]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+        let summary =
+            process_batch_with(
+                &replay_paths,
+                &dir,
+                false,
+                |replay_path, _| match replay_path.to_str().unwrap() {
+                    "error.replay" => Err(ProcessError::CreateOutputDirError(io::Error::new(
+                        io::ErrorKind::PermissionDenied,
+                        "read-only",
+                    ))),
+                    "panic.replay" => panic!("bad frame"),
+                    _ => Ok(()),
+                },
+            )
+            .unwrap();
+
+        assert_eq!((summary.succeeded, summary.failed), (1, 2));
+        let errors: Vec<Option<&str>> = summary
+            .results
+            .iter()
+            .map(|result| result.error.as_deref())
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                None,
+                Some("failed to create output directory: read-only"),
+                Some("panicked: bad frame"),
+            ]
+        );
+        assert_eq!(summary.results[1].output_dir, dir.join("error"));
+        assert!(dir.join("summary.json").is_file());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,4 +1,4 @@
-use crate::cleaner::BoostPickupKind;
+use crate::cleaner::{BoostPickup, BoostPickupKind};
 use lazy_static::lazy_static;
 use log::warn;
 use ndarray::prelude::*;
@@ -42,6 +42,76 @@ pub static BOOST_PADS_COORDS: [[f32; 3]; 34] = [
     [0.0, 4240.0, 70.0],
 ];
 
+/// Indices into `BOOST_PADS_COORDS` of the big (full boost) pads; all others are small.
+pub static FULL_BOOST_PAD_INDICES: [usize; 6] = [3, 4, 15, 18, 29, 30];
+
+/// Index into `BOOST_PADS_COORDS` of the pad nearest to the position.
+pub fn nearest_boost_pad_index(x: f32, y: f32) -> usize {
+    let distances = BOOST_PADS_COORDS
+        .iter()
+        .map(|coords| (coords[0] - x).powi(2) + (coords[1] - y).powi(2));
+    distances
+        .enumerate()
+        .fold((0, f32::INFINITY), |nearest, (pad_index, distance)| {
+            if distance < nearest.1 {
+                (pad_index, distance)
+            } else {
+                nearest
+            }
+        })
+        .0
+}
+
+/// The kind of pickup given by the pad at an index into `BOOST_PADS_COORDS`.
+pub fn boost_pad_kind(pad_index: usize) -> BoostPickupKind {
+    if FULL_BOOST_PAD_INDICES.contains(&pad_index) {
+        BoostPickupKind::Full
+    } else {
+        BoostPickupKind::Small
+    }
+}
+
+/// Pads become unavailable when picked up, and respawn after a fixed time or when the next goal is scored.
+/// All pads are reset for the kickoff after a goal; they are marked available from the goal frame itself.
+/// Returns each pad's availability per frame, indexed as `BOOST_PADS_COORDS`.
+pub fn boost_pads_availability(
+    boost_pickups: &[BoostPickup],
+    times: &[f32],
+    goal_frames: &[usize],
+) -> Vec<Vec<bool>> {
+    let mut pads_availability = vec![vec![true; times.len()]; BOOST_PADS_COORDS.len()];
+    for boost_pickup in boost_pickups.iter() {
+        let pad_index = match boost_pickup.pad_index {
+            Some(pad_index) => pad_index,
+            None => continue,
+        };
+        let picked_up_time = match times.get(boost_pickup.frame_number) {
+            Some(picked_up_time) => *picked_up_time,
+            None => continue,
+        };
+        let respawn_time = match boost_pad_kind(pad_index) {
+            BoostPickupKind::Full => FULL_BOOST_PAD_RESPAWN_TIME,
+            BoostPickupKind::Small => SMALL_BOOST_PAD_RESPAWN_TIME,
+        };
+        let reset_frame_number = goal_frames
+            .iter()
+            .filter(|goal_frame| **goal_frame > boost_pickup.frame_number)
+            .min()
+            .copied()
+            .unwrap_or(times.len());
+        for frame_number in boost_pickup.frame_number..reset_frame_number {
+            if times[frame_number] - picked_up_time >= respawn_time {
+                break;
+            }
+            pads_availability[pad_index][frame_number] = false;
+        }
+    }
+    pads_availability
+}
+
+pub static FULL_BOOST_PAD_RESPAWN_TIME: f32 = 10.0;
+pub static SMALL_BOOST_PAD_RESPAWN_TIME: f32 = 4.0;
+
 pub static FULL_BOOST_PAD_RADIUS: f32 = 208.0;
 pub static SMALL_BOOST_PAD_RADIUS: f32 = 144.0;
 // See https://www.youtube.com/watch?v=xgfa-qZyInw for more details regarding boost pads
@@ -51,15 +121,17 @@ lazy_static! {
     static ref FULL_BOOST_PADS: Vec<[f32; 3]> = {
         BOOST_PADS_COORDS
             .iter()
-            .filter(|coords| (coords[2] - 73.0).abs() < f32::EPSILON)
-            .cloned()
+            .enumerate()
+            .filter(|(pad_index, _)| FULL_BOOST_PAD_INDICES.contains(pad_index))
+            .map(|(_, coords)| *coords)
             .collect()
     };
     static ref SMALL_BOOST_PADS: Vec<[f32; 3]> = {
         BOOST_PADS_COORDS
             .iter()
-            .filter(|coords| (coords[2] - 70.0).abs() < f32::EPSILON)
-            .cloned()
+            .enumerate()
+            .filter(|(pad_index, _)| !FULL_BOOST_PAD_INDICES.contains(pad_index))
+            .map(|(_, coords)| *coords)
             .collect()
     };
     pub static ref BOOST_PAD_DISTANCE_CALCULATOR: BoostPadDistanceCalculator =
@@ -148,3 +220,91 @@ impl Default for BoostPadDistanceCalculator {
         BoostPadDistanceCalculator::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor_handlers::WrappedUniqueId;
+    use boxcars::ActorId;
+
+    fn boost_pickup(frame_number: usize, pad_index: usize) -> BoostPickup {
+        BoostPickup {
+            frame_number,
+            unique_id: WrappedUniqueId::Synthetic("a".to_string(), ActorId(0)),
+            pad_index: Some(pad_index),
+            kind: Some(boost_pad_kind(pad_index)),
+        }
+    }
+
+    fn times(frame_count: usize) -> Vec<f32> {
+        (0..frame_count)
+            .map(|frame_number| frame_number as f32)
+            .collect()
+    }
+
+    #[test]
+    fn nearest_pad_to_each_pad_is_itself() {
+        for (pad_index, coords) in BOOST_PADS_COORDS.iter().enumerate() {
+            assert_eq!(nearest_boost_pad_index(coords[0], coords[1]), pad_index);
+        }
+    }
+
+    #[test]
+    fn nearest_pad_to_nearby_positions() {
+        assert_eq!(nearest_boost_pad_index(-3000.0, -4000.0), 3);
+        assert_eq!(nearest_boost_pad_index(900.0, 100.0), 17);
+        assert_eq!(nearest_boost_pad_index(0.0, 5000.0), 33);
+    }
+
+    #[test]
+    fn full_boost_pads_are_the_corner_and_midfield_pads() {
+        for (pad_index, coords) in BOOST_PADS_COORDS.iter().enumerate() {
+            let is_full = boost_pad_kind(pad_index) == BoostPickupKind::Full;
+            assert_eq!(is_full, coords[2] > 71.0, "pad {}", pad_index);
+        }
+        assert_eq!(FULL_BOOST_PADS.len(), 6);
+        assert_eq!(SMALL_BOOST_PADS.len(), 28);
+    }
+
+    #[test]
+    fn pads_respawn_after_respawn_time() {
+        let boost_pickups = vec![boost_pickup(2, 0), boost_pickup(2, 3)];
+        let pads_availability = boost_pads_availability(&boost_pickups, &times(20), &[]);
+
+        let small_pad_unavailable: Vec<usize> = (0..20)
+            .filter(|frame_number| !pads_availability[0][*frame_number])
+            .collect();
+        assert_eq!(small_pad_unavailable, (2..6).collect::<Vec<usize>>());
+        let full_pad_unavailable: Vec<usize> = (0..20)
+            .filter(|frame_number| !pads_availability[3][*frame_number])
+            .collect();
+        assert_eq!(full_pad_unavailable, (2..12).collect::<Vec<usize>>());
+        assert!(pads_availability[1].iter().all(|available| *available));
+    }
+
+    #[test]
+    fn pads_respawn_at_next_goal() {
+        let boost_pickups = vec![boost_pickup(2, 3), boost_pickup(10, 0)];
+        let pads_availability = boost_pads_availability(&boost_pickups, &times(20), &[1, 5]);
+
+        // Reset by the goal at frame 5, not the earlier one at frame 1.
+        assert_eq!(
+            pads_availability[3][..7],
+            [true, true, false, false, false, true, true]
+        );
+        // No goal after the pickup, so the respawn timer applies.
+        assert!(!pads_availability[0][13]);
+        assert!(pads_availability[0][14]);
+    }
+
+    #[test]
+    fn pickups_without_pad_or_frame_are_ignored() {
+        let mut unknown_pad_boost_pickup = boost_pickup(2, 0);
+        unknown_pad_boost_pickup.pad_index = None;
+        let boost_pickups = vec![unknown_pad_boost_pickup, boost_pickup(30, 0)];
+        let pads_availability = boost_pads_availability(&boost_pickups, &times(20), &[]);
+        assert!(pads_availability
+            .iter()
+            .all(|pad_availability| pad_availability.iter().all(|available| *available)));
+    }
+}
@@ -1,18 +1,42 @@
-use crate::actor_handlers::TimeSeriesCarData;
-use crate::cleaner::boost_pad::BOOST_PAD_DISTANCE_CALCULATOR;
-use crate::cleaner::SMALL_BOOST_PAD_RADIUS;
-use crate::cleaner::SPAWN_DISTANCE_CALCULATOR;
+use crate::actor_handlers::{
+    BoostPickupData, TimeSeriesCarColumns, TimeSeriesCarData, WrappedUniqueId,
+};
+use crate::cleaner::GameMode;
+use crate::cleaner::{boost_pad_kind, nearest_boost_pad_index, SMALL_BOOST_PAD_RADIUS};
+use boxcars::ActorId;
 use log::warn;
 use ndarray_stats::errors::MinMaxError;
+use serde::Serialize;
+use std::collections::HashMap;
 use thiserror::Error;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Maximum frames between a replicated pickup and the boost increase it caused.
+static BOOST_PICKUP_MATCH_FRAMES: usize = 15;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
 pub enum BoostPickupKind {
     Full,
     Small,
 }
 
 impl BoostPickupKind {
+    /// Encodes a (possibly absent) pickup on a frame where boost was updated as 0 (none), 1 (small) or 2 (full).
+    pub fn to_column_value(boost_pickup_kind: Option<BoostPickupKind>) -> u8 {
+        match boost_pickup_kind {
+            Some(BoostPickupKind::Full) => 2,
+            Some(BoostPickupKind::Small) => 1,
+            None => 0,
+        }
+    }
+
+    pub fn from_column_value(column_value: u8) -> Option<BoostPickupKind> {
+        match column_value {
+            2 => Some(BoostPickupKind::Full),
+            1 => Some(BoostPickupKind::Small),
+            _ => None,
+        }
+    }
+
     pub fn detect_boost_pickup_kind(
         last_boost_amount: f32,
         new_boost_amount: f32,
@@ -20,7 +44,11 @@ impl BoostPickupKind {
         frame_number: usize,
         player_name: &str,
         replay_version: i32,
+        game_mode: GameMode,
     ) -> Result<Option<BoostPickupKind>, BoostPickupKindCalculationError> {
+        // Without the arena's boost pad layout and spawns, pickups are inferred from the boost increase alone.
+        let boost_pad_distance_calculator = game_mode.boost_pad_distance_calculator();
+        let spawn_distance_calculator = game_mode.spawn_distance_calculator();
         let boost_increase = new_boost_amount - last_boost_amount;
 
         let nearly_full_after_pickup = (100.0 - new_boost_amount) < 3.0;
@@ -47,9 +75,15 @@ impl BoostPickupKind {
 
             if nearly_full_after_pickup {
                 // Cannot differentiate between big and small boost pickup from increase.
-                if let Some(_car_data) = car_data {
+                // Without a boost pad layout, the kind is unknown.
+                if boost_pad_distance_calculator.is_none() {
+                    return Ok(None);
+                }
+                if let (Some(_car_data), Some(_boost_pad_distance_calculator)) =
+                    (car_data, boost_pad_distance_calculator)
+                {
                     return Ok(Some(
-                        BOOST_PAD_DISTANCE_CALCULATOR
+                        _boost_pad_distance_calculator
                             .calculate_boost_pad_collection_kind(
                                 _car_data.pos_x.ok_or(
                                     BoostPickupKindCalculationError::MissingCarPositionData,
@@ -70,22 +104,30 @@ impl BoostPickupKind {
                 return Ok(None);
             } else if boost_increase_is_nearly_small_amount {
                 if boost_amount_near_default {
-                    if let Some(_car_data) = car_data {
+                    if let (
+                        Some(_car_data),
+                        Some(_boost_pad_distance_calculator),
+                        Some(_spawn_distance_calculator),
+                    ) = (
+                        car_data,
+                        boost_pad_distance_calculator,
+                        spawn_distance_calculator,
+                    ) {
                         let x = _car_data
                             .pos_x
                             .ok_or(BoostPickupKindCalculationError::MissingCarPositionData)?;
                         let y = _car_data
                             .pos_y
                             .ok_or(BoostPickupKindCalculationError::MissingCarPositionData)?;
-                        let small_boost_pad_minimum_distance = BOOST_PAD_DISTANCE_CALCULATOR
+                        let small_boost_pad_minimum_distance = _boost_pad_distance_calculator
                             .calculate_minimum_small_boost_pad_distance(x, y)
                             .map_err(
                                 BoostPickupKindCalculationError::FailedToCalculateBoostDistanceMin,
                             )?;
                         let is_near_small_boost_pad = small_boost_pad_minimum_distance
                             < SMALL_BOOST_PAD_RADIUS
-                                + BOOST_PAD_DISTANCE_CALCULATOR.distance_buffer;
-                        let is_near_spawn_or_respawn = SPAWN_DISTANCE_CALCULATOR
+                                + _boost_pad_distance_calculator.distance_buffer;
+                        let is_near_spawn_or_respawn = _spawn_distance_calculator
                             .check_if_near_spawn(x, y)
                             .map_err(
                                 BoostPickupKindCalculationError::FailedToCalculateSpawnDistanceMin,
@@ -104,7 +146,7 @@ impl BoostPickupKind {
                         } else {
                             panic!("Code should not reach here as all boolean combinations should be covered.");
                         }
-                    } else {
+                    } else if car_data.is_none() {
                         warn!(
                             "Cannot infer boost pickup kind as car data is missing. (Frame {}; {:?}) (boost_increase_is_small, boost_increase_is_nearly_small_amount)",
                             frame_number, player_name
@@ -116,14 +158,16 @@ impl BoostPickupKind {
                 // Boost increase is likely negligible and is due to boost usage overestimation (i.e. boost amount increases slightly when updated true value comes through).
                 return Ok(None);
             } else if boost_amount_near_default {
-                if let Some(_car_data) = car_data {
+                if let (Some(_car_data), Some(_spawn_distance_calculator)) =
+                    (car_data, spawn_distance_calculator)
+                {
                     let x = _car_data
                         .pos_x
                         .ok_or(BoostPickupKindCalculationError::MissingCarPositionData)?;
                     let y = _car_data
                         .pos_y
                         .ok_or(BoostPickupKindCalculationError::MissingCarPositionData)?;
-                    if SPAWN_DISTANCE_CALCULATOR
+                    if _spawn_distance_calculator
                         .check_if_near_spawn(x, y)
                         .map_err(
                             BoostPickupKindCalculationError::FailedToCalculateSpawnDistanceMin,
@@ -139,7 +183,7 @@ impl BoostPickupKind {
                         return Ok(Some(BoostPickupKind::Small));
                     };
                 } else {
-                    // Spawn (as lacking car data and boost amount near default)
+                    // Spawn (as lacking car data or spawn locations, and boost amount near default)
                     return Ok(None);
                 };
             } else {
@@ -161,6 +205,176 @@ impl BoostPickupKind {
     }
 }
 
+/// A boost pad pickup replicated by the pad actor.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoostPickup {
+    pub frame_number: usize,
+    pub unique_id: WrappedUniqueId,
+    /// Index into `BOOST_PADS_COORDS`, for arenas with the standard pad layout.
+    pub pad_index: Option<usize>,
+    pub kind: Option<BoostPickupKind>,
+}
+
+impl BoostPickup {
+    /// Matches each pad actor to a pad in `BOOST_PADS_COORDS`.
+    /// Pad actors are rarely spawned with a location, so each is matched to the pad nearest to the cars picking it up.
+    pub fn from_boost_pickups_data(
+        boost_pickups_data: &[BoostPickupData],
+        players_time_series_car_data: &HashMap<WrappedUniqueId, TimeSeriesCarColumns>,
+        game_mode: GameMode,
+    ) -> Vec<Self> {
+        let mut pads_votes: HashMap<ActorId, HashMap<usize, usize>> = HashMap::new();
+        if game_mode.uses_soccar_arena() {
+            for boost_pickup_data in boost_pickups_data.iter() {
+                let position = match boost_pickup_data.pad_location {
+                    Some(pad_location) => Some((pad_location[0], pad_location[1])),
+                    None => players_time_series_car_data
+                        .get(&boost_pickup_data.player_wrapped_unique_id)
+                        .and_then(|time_series_car_data| {
+                            time_series_car_data.get(boost_pickup_data.frame_number)
+                        })
+                        .and_then(|car_data| Some((car_data.pos_x?, car_data.pos_y?))),
+                };
+                if let Some((x, y)) = position {
+                    *pads_votes
+                        .entry(boost_pickup_data.pad_actor_id)
+                        .or_default()
+                        .entry(nearest_boost_pad_index(x, y))
+                        .or_insert(0) += 1;
+                }
+            }
+        }
+        let pads_index: HashMap<ActorId, usize> = pads_votes
+            .into_iter()
+            .filter_map(|(pad_actor_id, pad_votes)| {
+                pad_votes
+                    .into_iter()
+                    .max_by_key(|(pad_index, votes)| (*votes, std::cmp::Reverse(*pad_index)))
+                    .map(|(pad_index, _)| (pad_actor_id, pad_index))
+            })
+            .collect();
+
+        boost_pickups_data
+            .iter()
+            .map(|boost_pickup_data| {
+                let pad_index = pads_index.get(&boost_pickup_data.pad_actor_id).copied();
+                Self {
+                    frame_number: boost_pickup_data.frame_number,
+                    unique_id: boost_pickup_data.player_wrapped_unique_id.clone(),
+                    pad_index,
+                    kind: pad_index.map(boost_pad_kind),
+                }
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum BoostPickupDisagreementKind {
+    /// Replicated pickup without a matching boost increase.
+    MissingFromHeuristic,
+    /// Boost increase detected as a pickup without a matching replicated pickup.
+    MissingFromReplicated,
+    KindMismatch,
+}
+
+/// A difference between replicated pickups and those inferred from boost amounts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BoostPickupDisagreement {
+    pub frame_number: usize,
+    pub unique_id: WrappedUniqueId,
+    pub kind: BoostPickupDisagreementKind,
+    pub replicated_kind: Option<BoostPickupKind>,
+    pub heuristic_kind: Option<BoostPickupKind>,
+}
+
+impl BoostPickupDisagreement {
+    /// Matches replicated pickups to the inferred `boost_pickup` column, within `BOOST_PICKUP_MATCH_FRAMES`.
+    /// Replays without replicated pickups have nothing to reconcile.
+    pub fn reconcile(
+        boost_pickups: &[BoostPickup],
+        players_time_series_boost_pickup_data: &HashMap<WrappedUniqueId, Vec<Option<u8>>>,
+    ) -> Vec<Self> {
+        let mut disagreements = vec![];
+        if boost_pickups.is_empty() {
+            return disagreements;
+        }
+        for (wrapped_unique_id, time_series_boost_pickup_data) in
+            players_time_series_boost_pickup_data.iter()
+        {
+            let heuristic_pickups: Vec<(usize, BoostPickupKind)> = time_series_boost_pickup_data
+                .iter()
+                .enumerate()
+                .filter_map(|(frame_number, column_value)| {
+                    Some((
+                        frame_number,
+                        BoostPickupKind::from_column_value((*column_value)?)?,
+                    ))
+                })
+                .collect();
+            let mut is_matched = vec![false; heuristic_pickups.len()];
+
+            for boost_pickup in boost_pickups
+                .iter()
+                .filter(|boost_pickup| &boost_pickup.unique_id == wrapped_unique_id)
+            {
+                // Match the nearest unmatched boost increase in the window.
+                let matched_index = heuristic_pickups
+                    .iter()
+                    .enumerate()
+                    .filter(|(index, _)| !is_matched[*index])
+                    .map(|(index, (frame_number, _))| {
+                        let frames_apart = frame_number.max(&boost_pickup.frame_number)
+                            - frame_number.min(&boost_pickup.frame_number);
+                        (index, frames_apart)
+                    })
+                    .filter(|(_, frames_apart)| *frames_apart <= BOOST_PICKUP_MATCH_FRAMES)
+                    .min_by_key(|(_, frames_apart)| *frames_apart)
+                    .map(|(index, _)| index);
+                match matched_index {
+                    Some(index) => {
+                        is_matched[index] = true;
+                        let heuristic_kind = heuristic_pickups[index].1;
+                        if boost_pickup.kind.is_some() && boost_pickup.kind != Some(heuristic_kind)
+                        {
+                            disagreements.push(Self {
+                                frame_number: boost_pickup.frame_number,
+                                unique_id: wrapped_unique_id.clone(),
+                                kind: BoostPickupDisagreementKind::KindMismatch,
+                                replicated_kind: boost_pickup.kind,
+                                heuristic_kind: Some(heuristic_kind),
+                            });
+                        }
+                    }
+                    None => disagreements.push(Self {
+                        frame_number: boost_pickup.frame_number,
+                        unique_id: wrapped_unique_id.clone(),
+                        kind: BoostPickupDisagreementKind::MissingFromHeuristic,
+                        replicated_kind: boost_pickup.kind,
+                        heuristic_kind: None,
+                    }),
+                }
+            }
+
+            for ((frame_number, heuristic_kind), _) in heuristic_pickups
+                .iter()
+                .zip(is_matched.iter())
+                .filter(|(_, is_matched)| !**is_matched)
+            {
+                disagreements.push(Self {
+                    frame_number: *frame_number,
+                    unique_id: wrapped_unique_id.clone(),
+                    kind: BoostPickupDisagreementKind::MissingFromReplicated,
+                    replicated_kind: None,
+                    heuristic_kind: Some(*heuristic_kind),
+                });
+            }
+        }
+        disagreements.sort_by_key(|disagreement| disagreement.frame_number);
+        disagreements
+    }
+}
+
 #[derive(Error, Debug, Clone, PartialEq, Eq)]
 pub enum BoostPickupKindCalculationError {
     #[error("missing car position data")]
@@ -170,3 +384,161 @@ pub enum BoostPickupKindCalculationError {
     #[error("failed to calculate minimum distance to spawn location")]
     FailedToCalculateSpawnDistanceMin(MinMaxError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cleaner::BOOST_PADS_COORDS;
+
+    fn player(name: &str) -> WrappedUniqueId {
+        WrappedUniqueId::Synthetic(name.to_string(), ActorId(0))
+    }
+
+    fn boost_pickup_data(
+        frame_number: usize,
+        pad_actor_id: i32,
+        pad_location: Option<[f32; 3]>,
+    ) -> BoostPickupData {
+        BoostPickupData {
+            frame_number,
+            pad_actor_id: ActorId(pad_actor_id),
+            pad_location,
+            player_wrapped_unique_id: player("a"),
+        }
+    }
+
+    fn boost_pickup(frame_number: usize, kind: BoostPickupKind) -> BoostPickup {
+        BoostPickup {
+            frame_number,
+            unique_id: player("a"),
+            pad_index: None,
+            kind: Some(kind),
+        }
+    }
+
+    #[test]
+    fn pickup_kind_to_nearly_full_is_unknown_without_boost_pads() {
+        let detect = |game_mode| {
+            BoostPickupKind::detect_boost_pickup_kind(90.0, 99.0, None, 0, "a", 10, game_mode)
+                .unwrap()
+        };
+        assert_eq!(detect(GameMode::Hoops), None);
+        assert_eq!(detect(GameMode::Dropshot), None);
+    }
+
+    #[test]
+    fn pads_are_matched_by_majority_of_car_positions() {
+        let mut time_series_car_data = TimeSeriesCarColumns::new(3);
+        // Two pickups near pad 0, and one (e.g. a stale position) near pad 7.
+        for (frame_number, (x, y)) in [(0.0, -4200.0), (20.0, -4250.0), (0.0, -2800.0)]
+            .iter()
+            .enumerate()
+        {
+            time_series_car_data.insert(
+                frame_number,
+                TimeSeriesCarData {
+                    pos_x: Some(*x),
+                    pos_y: Some(*y),
+                    ..Default::default()
+                },
+            );
+        }
+        let mut players_time_series_car_data = HashMap::new();
+        players_time_series_car_data.insert(player("a"), time_series_car_data);
+        let boost_pickups_data = vec![
+            boost_pickup_data(0, 1, None),
+            boost_pickup_data(1, 1, None),
+            boost_pickup_data(2, 1, None),
+            // Spawned with a location, so car positions are not needed.
+            boost_pickup_data(2, 2, Some(BOOST_PADS_COORDS[3])),
+        ];
+
+        let boost_pickups = BoostPickup::from_boost_pickups_data(
+            &boost_pickups_data,
+            &players_time_series_car_data,
+            GameMode::Soccar,
+        );
+        let pads_index: Vec<Option<usize>> = boost_pickups
+            .iter()
+            .map(|boost_pickup| boost_pickup.pad_index)
+            .collect();
+        assert_eq!(pads_index, vec![Some(0), Some(0), Some(0), Some(3)]);
+        assert_eq!(boost_pickups[0].kind, Some(BoostPickupKind::Small));
+        assert_eq!(boost_pickups[3].kind, Some(BoostPickupKind::Full));
+
+        let hoops_boost_pickups = BoostPickup::from_boost_pickups_data(
+            &boost_pickups_data,
+            &players_time_series_car_data,
+            GameMode::Hoops,
+        );
+        assert!(hoops_boost_pickups
+            .iter()
+            .all(|boost_pickup| boost_pickup.pad_index.is_none()));
+    }
+
+    #[test]
+    fn reconcile_finds_disagreements() {
+        let mut time_series_boost_pickup_data = vec![None; 200];
+        // Matches the replicated pickup at frame 10.
+        time_series_boost_pickup_data[12] = Some(1);
+        // Matches the replicated pickup at frame 50, but with a different kind.
+        time_series_boost_pickup_data[50] = Some(2);
+        // No replicated pickup.
+        time_series_boost_pickup_data[150] = Some(1);
+        // Boost updated without a pickup.
+        time_series_boost_pickup_data[160] = Some(0);
+        let mut players_time_series_boost_pickup_data = HashMap::new();
+        players_time_series_boost_pickup_data.insert(player("a"), time_series_boost_pickup_data);
+        let boost_pickups = vec![
+            boost_pickup(10, BoostPickupKind::Small),
+            boost_pickup(50, BoostPickupKind::Small),
+            // Outside the match window of any boost increase.
+            boost_pickup(100, BoostPickupKind::Full),
+        ];
+
+        let disagreements = BoostPickupDisagreement::reconcile(
+            &boost_pickups,
+            &players_time_series_boost_pickup_data,
+        );
+        let disagreements: Vec<(usize, BoostPickupDisagreementKind)> = disagreements
+            .iter()
+            .map(|disagreement| (disagreement.frame_number, disagreement.kind))
+            .collect();
+        assert_eq!(
+            disagreements,
+            vec![
+                (50, BoostPickupDisagreementKind::KindMismatch),
+                (100, BoostPickupDisagreementKind::MissingFromHeuristic),
+                (150, BoostPickupDisagreementKind::MissingFromReplicated),
+            ]
+        );
+    }
+
+    #[test]
+    fn reconcile_matches_nearest_boost_increase() {
+        let mut time_series_boost_pickup_data = vec![None; 40];
+        time_series_boost_pickup_data[5] = Some(1);
+        time_series_boost_pickup_data[18] = Some(1);
+        let mut players_time_series_boost_pickup_data = HashMap::new();
+        players_time_series_boost_pickup_data.insert(player("a"), time_series_boost_pickup_data);
+        let boost_pickups = vec![
+            boost_pickup(17, BoostPickupKind::Small),
+            boost_pickup(6, BoostPickupKind::Small),
+        ];
+        assert!(BoostPickupDisagreement::reconcile(
+            &boost_pickups,
+            &players_time_series_boost_pickup_data
+        )
+        .is_empty());
+    }
+
+    #[test]
+    fn nothing_to_reconcile_without_replicated_pickups() {
+        let mut players_time_series_boost_pickup_data = HashMap::new();
+        players_time_series_boost_pickup_data.insert(player("a"), vec![Some(1); 10]);
+        assert!(
+            BoostPickupDisagreement::reconcile(&[], &players_time_series_boost_pickup_data)
+                .is_empty()
+        );
+    }
+}
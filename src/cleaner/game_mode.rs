@@ -0,0 +1,324 @@
+use crate::cleaner::{
+    BoostPadDistanceCalculator, SpawnDistanceCalculator, BOOST_PAD_DISTANCE_CALCULATOR,
+    SPAWN_DISTANCE_CALCULATOR,
+};
+use boxcars::Replay;
+use serde::Serialize;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum GameMode {
+    Soccar,
+    Hoops,
+    Dropshot,
+    SnowDay,
+    Rumble,
+    Heatseeker,
+    Unknown,
+}
+
+impl GameMode {
+    /// Detects the game mode from the game event and ball archetypes spawned in the network frames, falling back to the map name.
+    /// Without network frames, only the map name is used: the replay's object names list every archetype, whether spawned or not.
+    /// Maps without a dedicated mode are then taken as Soccar, as variants played on them (e.g. Rumble) cannot be told apart.
+    pub fn from_replay(replay: &Replay, map_name: Option<&str>) -> Self {
+        let object_names = match get_spawned_object_names(replay) {
+            Some(object_names) => object_names,
+            None => {
+                return map_name
+                    .map(|map_name| GameMode::from_map_name(map_name).unwrap_or(GameMode::Soccar))
+                    .unwrap_or(GameMode::Unknown)
+            }
+        };
+        let has_object = |prefix: &str| object_names.iter().any(|name| name.starts_with(prefix));
+
+        if has_object("Archetypes.GameEvent.GameEvent_Basketball")
+            || has_object("Archetypes.Ball.Ball_Basketball")
+        {
+            GameMode::Hoops
+        } else if has_object("Archetypes.GameEvent.GameEvent_Breakout")
+            || has_object("Archetypes.Ball.Ball_Breakout")
+        {
+            GameMode::Dropshot
+        } else if has_object("Archetypes.GameEvent.GameEvent_Hockey")
+            || has_object("Archetypes.Ball.Ball_Puck")
+        {
+            GameMode::SnowDay
+        } else if has_object("Archetypes.GameEvent.GameEvent_Items")
+            || has_object("Archetypes.SpecialPickups.")
+        {
+            GameMode::Rumble
+        } else if has_object("Archetypes.Ball.Ball_God") {
+            GameMode::Heatseeker
+        } else if let Some(game_mode) = map_name.and_then(GameMode::from_map_name) {
+            game_mode
+        } else if has_object("Archetypes.GameEvent.GameEvent_Soccar") {
+            GameMode::Soccar
+        } else {
+            GameMode::Unknown
+        }
+    }
+
+    /// Detects modes with dedicated maps.
+    pub fn from_map_name(map_name: &str) -> Option<Self> {
+        let map_name = map_name.to_lowercase();
+        if map_name.starts_with("hoops") {
+            Some(GameMode::Hoops)
+        } else if map_name.starts_with("shattershot") {
+            Some(GameMode::Dropshot)
+        } else {
+            None
+        }
+    }
+
+    pub fn arena(self) -> Arena {
+        match self {
+            GameMode::Hoops => Arena::HOOPS,
+            GameMode::Dropshot => Arena::DROPSHOT,
+            _ => Arena::SOCCAR,
+        }
+    }
+
+    pub fn default_ball_type(self) -> BallType {
+        match self {
+            GameMode::Hoops => BallType::Basketball,
+            GameMode::Dropshot => BallType::Breakout,
+            GameMode::SnowDay => BallType::Puck,
+            GameMode::Heatseeker => BallType::Heatseeker,
+            _ => BallType::Default,
+        }
+    }
+
+    /// Whether the mode is played in the standard soccar arena, with its boost pads and kickoff spawns.
+    pub fn uses_soccar_arena(self) -> bool {
+        self.arena() == Arena::SOCCAR
+    }
+
+    /// The mode's boost pad layout, if known.
+    /// Only the soccar arena's layout is known, so this is None for Hoops and Dropshot
+    /// (and their pickups are neither matched to pads nor classified when ambiguous).
+    pub fn boost_pad_distance_calculator(self) -> Option<&'static BoostPadDistanceCalculator> {
+        if self.uses_soccar_arena() {
+            Some(&*BOOST_PAD_DISTANCE_CALCULATOR)
+        } else {
+            None
+        }
+    }
+
+    /// The mode's kickoff spawn and respawn locations, if known.
+    pub fn spawn_distance_calculator(self) -> Option<&'static SpawnDistanceCalculator> {
+        if self.uses_soccar_arena() {
+            Some(&*SPAWN_DISTANCE_CALCULATOR)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum BallType {
+    Default,
+    Puck,
+    Basketball,
+    /// Dropshot's ball.
+    Breakout,
+    Cube,
+    Heatseeker,
+    Other,
+}
+
+impl BallType {
+    /// Detects the ball type from the first ball archetype spawned (or in the replay's object names).
+    pub fn from_replay(replay: &Replay, game_mode: GameMode) -> Self {
+        match &replay.network_frames {
+            Some(network_frames) => network_frames
+                .frames
+                .iter()
+                .flat_map(|frame| frame.new_actors.iter())
+                .map(|new_actor| replay.objects[usize::from(new_actor.object_id)].as_str())
+                .find(|object_name| object_name.starts_with("Archetypes.Ball."))
+                .map(BallType::from_object_name),
+            None => {
+                let ball_types: HashSet<BallType> = replay
+                    .objects
+                    .iter()
+                    .filter(|object_name| object_name.starts_with("Archetypes.Ball."))
+                    .map(|object_name| BallType::from_object_name(object_name))
+                    .collect();
+                // The object names may list several balls, so this is only used if unambiguous.
+                if ball_types.len() == 1 {
+                    ball_types.into_iter().next()
+                } else {
+                    None
+                }
+            }
+        }
+        .unwrap_or_else(|| game_mode.default_ball_type())
+    }
+
+    pub fn from_object_name(object_name: &str) -> Self {
+        let object_name = object_name.to_lowercase();
+        if object_name.contains("ball_default") {
+            BallType::Default
+        } else if object_name.contains("puck") {
+            BallType::Puck
+        } else if object_name.contains("basketball") {
+            BallType::Basketball
+        } else if object_name.contains("breakout") {
+            BallType::Breakout
+        } else if object_name.contains("cube") {
+            BallType::Cube
+        } else if object_name.contains("ball_god") {
+            BallType::Heatseeker
+        } else {
+            BallType::Other
+        }
+    }
+
+    /// Horizontal collision radius.
+    pub fn radius(self) -> f32 {
+        match self {
+            BallType::Puck => 114.25,
+            BallType::Basketball => 96.38,
+            BallType::Breakout => 100.25,
+            _ => 92.75,
+        }
+    }
+
+    /// Height of the ball's centre when resting on the floor.
+    pub fn rest_height(self) -> f32 {
+        match self {
+            // The puck is a flat cylinder, 62.5 tall.
+            BallType::Puck => 31.25,
+            _ => self.radius(),
+        }
+    }
+}
+
+/// Arena dimensions, from the centre of the floor. Non-soccar arenas use their bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Arena {
+    pub side_wall: f32,
+    pub back_wall: f32,
+    pub ceiling: f32,
+    pub goal_depth: f32,
+}
+
+impl Arena {
+    pub const SOCCAR: Arena = Arena {
+        side_wall: 4096.0,
+        back_wall: 5120.0,
+        ceiling: 2044.0,
+        goal_depth: 880.0,
+    };
+    pub const HOOPS: Arena = Arena {
+        side_wall: 2966.67,
+        back_wall: 3581.0,
+        ceiling: 1820.0,
+        goal_depth: 0.0,
+    };
+    /// The hexagonal arena's corners reach the side walls.
+    pub const DROPSHOT: Arena = Arena {
+        side_wall: 5026.0,
+        back_wall: 4555.0,
+        ceiling: 2010.0,
+        goal_depth: 0.0,
+    };
+}
+
+fn get_spawned_object_names(replay: &Replay) -> Option<HashSet<&str>> {
+    Some(
+        replay
+            .network_frames
+            .as_ref()?
+            .frames
+            .iter()
+            .flat_map(|frame| frame.new_actors.iter())
+            .map(|new_actor| replay.objects[usize::from(new_actor.object_id)].as_str())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use boxcars::{NetworkFrames, Replay};
+
+    fn replay_with_objects(objects: &[&str], network_frames: Option<NetworkFrames>) -> Replay {
+        Replay {
+            header_size: 0,
+            header_crc: 0,
+            major_version: 868,
+            minor_version: 29,
+            net_version: Some(10),
+            game_type: "TAGame.Replay_Soccar_TA".to_string(),
+            properties: vec![],
+            content_size: 0,
+            content_crc: 0,
+            network_frames,
+            levels: vec![],
+            keyframes: vec![],
+            debug_info: vec![],
+            tick_marks: vec![],
+            packages: vec![],
+            objects: objects.iter().map(|object| object.to_string()).collect(),
+            names: vec![],
+            class_indices: vec![],
+            net_cache: vec![],
+        }
+    }
+
+    #[test]
+    fn header_only_mode_ignores_unspawned_archetypes() {
+        let replay = replay_with_objects(
+            &[
+                "Archetypes.Ball.Ball_Default",
+                "Archetypes.Ball.Ball_Basketball",
+                "Archetypes.GameEvent.GameEvent_Soccar",
+            ],
+            None,
+        );
+        assert_eq!(
+            GameMode::from_replay(&replay, Some("Stadium_P")),
+            GameMode::Soccar
+        );
+        assert_eq!(
+            GameMode::from_replay(&replay, Some("HoopsStadium_P")),
+            GameMode::Hoops
+        );
+        assert_eq!(GameMode::from_replay(&replay, None), GameMode::Unknown);
+    }
+
+    #[test]
+    fn mode_from_map_name() {
+        assert_eq!(
+            GameMode::from_map_name("HoopsStreet_P"),
+            Some(GameMode::Hoops)
+        );
+        assert_eq!(
+            GameMode::from_map_name("ShatterShot_P"),
+            Some(GameMode::Dropshot)
+        );
+        assert_eq!(GameMode::from_map_name("Park_P"), None);
+    }
+
+    #[test]
+    fn ball_type_from_object_name() {
+        assert_eq!(
+            BallType::from_object_name("Archetypes.Ball.Ball_Default"),
+            BallType::Default
+        );
+        assert_eq!(
+            BallType::from_object_name("Archetypes.Ball.Ball_Puck"),
+            BallType::Puck
+        );
+        assert_eq!(
+            BallType::from_object_name("Archetypes.Ball.CubeBall"),
+            BallType::Cube
+        );
+        assert_eq!(
+            BallType::from_object_name("Archetypes.Ball.Ball_God"),
+            BallType::Heatseeker
+        );
+    }
+}
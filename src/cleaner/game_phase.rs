@@ -0,0 +1,166 @@
+use crate::actor_handlers::TimeSeriesGameEventColumns;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamePhase {
+    /// Before play starts (kickoff countdown, or before the first kickoff).
+    Countdown,
+    Play,
+    /// After a goal is scored (goal explosion and replay), until the next countdown starts.
+    GoalScored,
+    /// After the match has ended.
+    PostGame,
+}
+
+impl GamePhase {
+    /// Encodes the phase as 0 (countdown), 1 (play), 2 (goal scored) or 3 (post-game).
+    pub fn to_column_value(self) -> u8 {
+        match self {
+            GamePhase::Countdown => 0,
+            GamePhase::Play => 1,
+            GamePhase::GoalScored => 2,
+            GamePhase::PostGame => 3,
+        }
+    }
+
+    pub fn is_active(self) -> bool {
+        self == GamePhase::Play
+    }
+
+    /// Derives each frame's phase from the game event's countdown and match-ended attributes and the header's goal frames.
+    /// If the countdown is never replicated, kickoffs are detected from the ball being hit instead,
+    /// so play only starts at the first touch.
+    pub fn from_game_event_data(
+        time_series_game_event_data: &TimeSeriesGameEventColumns,
+        goal_frames: &[usize],
+        frame_count: usize,
+    ) -> Vec<GamePhase> {
+        let has_countdown = (0..frame_count).any(|frame_number| {
+            time_series_game_event_data
+                .get(frame_number)
+                .and_then(|data| data.replicated_game_state_time_remaining)
+                .is_some()
+        });
+        let mut game_phases = Vec::with_capacity(frame_count);
+        let mut game_phase = GamePhase::Countdown;
+        for frame_number in 0..frame_count {
+            let game_event_data = time_series_game_event_data.get(frame_number);
+            let countdown_time_remaining = if has_countdown {
+                game_event_data.and_then(|data| data.replicated_game_state_time_remaining)
+            } else {
+                // The ball is reset to not hit for each kickoff.
+                match game_event_data.and_then(|data| data.ball_has_been_hit) {
+                    Some(true) => Some(0),
+                    Some(false) => Some(1),
+                    None => None,
+                }
+            };
+            let match_ended = game_event_data
+                .and_then(|data| data.match_ended)
+                .unwrap_or(false);
+
+            game_phase = if match_ended || game_phase == GamePhase::PostGame {
+                GamePhase::PostGame
+            } else if goal_frames.contains(&frame_number) {
+                GamePhase::GoalScored
+            } else {
+                match (game_phase, countdown_time_remaining) {
+                    // Countdown restarts for the next kickoff (or overtime).
+                    (GamePhase::GoalScored, Some(time_remaining))
+                    | (GamePhase::Play, Some(time_remaining))
+                        if time_remaining > 0 =>
+                    {
+                        GamePhase::Countdown
+                    }
+                    (GamePhase::Countdown, Some(0)) => GamePhase::Play,
+                    _ => game_phase,
+                }
+            };
+            game_phases.push(game_phase);
+        }
+        game_phases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor_handlers::TimeSeriesGameEventData;
+
+    fn game_event_data(
+        replicated_game_state_time_remaining: Option<i32>,
+        ball_has_been_hit: Option<bool>,
+        match_ended: Option<bool>,
+    ) -> TimeSeriesGameEventData {
+        TimeSeriesGameEventData {
+            seconds_remaining: Some(300),
+            replicated_game_state_time_remaining,
+            is_overtime: Some(false),
+            ball_has_been_hit,
+            match_ended,
+        }
+    }
+
+    fn game_event_columns(data: Vec<TimeSeriesGameEventData>) -> TimeSeriesGameEventColumns {
+        let mut time_series_game_event_data = TimeSeriesGameEventColumns::new(data.len());
+        for (frame_number, game_event_data) in data.into_iter().enumerate() {
+            time_series_game_event_data.insert(frame_number, game_event_data);
+        }
+        time_series_game_event_data
+    }
+
+    #[test]
+    fn phases_from_countdown() {
+        let time_series_game_event_data = game_event_columns(vec![
+            game_event_data(Some(3), Some(false), None),
+            game_event_data(Some(0), Some(false), None),
+            game_event_data(Some(0), Some(true), None),
+            // Goal
+            game_event_data(Some(0), Some(true), None),
+            game_event_data(Some(0), Some(true), None),
+            game_event_data(Some(3), Some(false), None),
+            game_event_data(Some(0), Some(false), None),
+            game_event_data(Some(0), Some(true), Some(true)),
+            game_event_data(Some(3), Some(false), None),
+        ]);
+        let game_phases = GamePhase::from_game_event_data(&time_series_game_event_data, &[3], 9);
+        assert_eq!(
+            game_phases,
+            vec![
+                GamePhase::Countdown,
+                GamePhase::Play,
+                GamePhase::Play,
+                GamePhase::GoalScored,
+                GamePhase::GoalScored,
+                GamePhase::Countdown,
+                GamePhase::Play,
+                GamePhase::PostGame,
+                GamePhase::PostGame,
+            ]
+        );
+    }
+
+    #[test]
+    fn phases_from_ball_hit_without_countdown() {
+        let time_series_game_event_data = game_event_columns(vec![
+            game_event_data(None, None, None),
+            game_event_data(None, Some(false), None),
+            game_event_data(None, Some(true), None),
+            // Goal
+            game_event_data(None, Some(true), None),
+            game_event_data(None, Some(false), None),
+            game_event_data(None, Some(true), None),
+        ]);
+        let game_phases = GamePhase::from_game_event_data(&time_series_game_event_data, &[3], 6);
+        assert_eq!(
+            game_phases,
+            vec![
+                GamePhase::Countdown,
+                GamePhase::Countdown,
+                GamePhase::Play,
+                GamePhase::GoalScored,
+                GamePhase::Countdown,
+                GamePhase::Play,
+            ]
+        );
+    }
+}
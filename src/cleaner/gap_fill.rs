@@ -0,0 +1,315 @@
+use crate::actor_handlers::{
+    TimeSeriesBallColumns, TimeSeriesBallData, TimeSeriesCarColumns, TimeSeriesCarData,
+};
+use crate::frame_parser::TimeSeriesReplayColumns;
+use serde::Serialize;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GapFillMethod {
+    /// Repeats the last known frame's data.
+    ForwardFill,
+    /// Moves the last known position along the last known velocity, repeating all other data.
+    Extrapolate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GapFillOptions {
+    pub method: GapFillMethod,
+    /// Gaps longer than this many frames are left unfilled.
+    pub max_gap_frames: usize,
+}
+
+impl Default for GapFillOptions {
+    fn default() -> Self {
+        Self {
+            method: GapFillMethod::Extrapolate,
+            max_gap_frames: 10,
+        }
+    }
+}
+
+/// Frames missing data between an actor's first and last frames with data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct GapStats {
+    pub missing_frames: usize,
+    pub gap_count: usize,
+    pub longest_gap_frames: usize,
+    pub filled_frames: usize,
+}
+
+/// Time-series data after gap filling, marking which frames were filled.
+/// The data is only copied if any frames were filled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GapFilled<T> {
+    pub filled_data: Option<T>,
+    pub is_filled: Vec<bool>,
+    pub stats: GapStats,
+}
+
+impl<T> GapFilled<T> {
+    /// The filled data, or `original` (the data the gaps were found in) if no frames were filled.
+    pub fn data<'a>(&'a self, original: &'a T) -> &'a T {
+        self.filled_data.as_ref().unwrap_or(original)
+    }
+}
+
+/// Fills gaps in a player's car data.
+/// Gaps spanning any of `boundary_frames` (the player's demolitions and goals, after which cars respawn) are never filled.
+pub fn fill_car_data_gaps(
+    time_series_car_data: &TimeSeriesCarColumns,
+    time_series_replay_data: &TimeSeriesReplayColumns,
+    boundary_frames: &[usize],
+    options: Option<GapFillOptions>,
+) -> GapFilled<TimeSeriesCarColumns> {
+    let mut filled_car_data = None;
+    let (is_filled, stats) = fill_gaps(
+        &time_series_car_data.is_set,
+        &time_series_replay_data.time,
+        boundary_frames,
+        options,
+        |source_frame_number, frame_number, elapsed| {
+            if let Some(car_data) = time_series_car_data.get(source_frame_number) {
+                filled_car_data
+                    .get_or_insert_with(|| time_series_car_data.clone())
+                    .insert(
+                        frame_number,
+                        TimeSeriesCarData {
+                            pos_x: extrapolate_position(car_data.pos_x, car_data.vel_x, elapsed),
+                            pos_y: extrapolate_position(car_data.pos_y, car_data.vel_y, elapsed),
+                            pos_z: extrapolate_position(car_data.pos_z, car_data.vel_z, elapsed),
+                            ..car_data
+                        },
+                    );
+            }
+        },
+    );
+    GapFilled {
+        filled_data: filled_car_data,
+        is_filled,
+        stats,
+    }
+}
+
+/// Fills gaps in the ball's data.
+/// Gaps spanning any of `boundary_frames` (goals, after which the ball is reset) are never filled.
+pub fn fill_ball_data_gaps(
+    time_series_ball_data: &TimeSeriesBallColumns,
+    time_series_replay_data: &TimeSeriesReplayColumns,
+    boundary_frames: &[usize],
+    options: Option<GapFillOptions>,
+) -> GapFilled<TimeSeriesBallColumns> {
+    let mut filled_ball_data = None;
+    let (is_filled, stats) = fill_gaps(
+        &time_series_ball_data.is_set,
+        &time_series_replay_data.time,
+        boundary_frames,
+        options,
+        |source_frame_number, frame_number, elapsed| {
+            if let Some(ball_data) = time_series_ball_data.get(source_frame_number) {
+                filled_ball_data
+                    .get_or_insert_with(|| time_series_ball_data.clone())
+                    .insert(
+                        frame_number,
+                        TimeSeriesBallData {
+                            pos_x: extrapolate_position(ball_data.pos_x, ball_data.vel_x, elapsed),
+                            pos_y: extrapolate_position(ball_data.pos_y, ball_data.vel_y, elapsed),
+                            pos_z: extrapolate_position(ball_data.pos_z, ball_data.vel_z, elapsed),
+                            ..ball_data
+                        },
+                    );
+            }
+        },
+    );
+    GapFilled {
+        filled_data: filled_ball_data,
+        is_filled,
+        stats,
+    }
+}
+
+/// Finds gaps and calls `fill(source_frame_number, frame_number, elapsed)` for each frame to be filled,
+/// where `elapsed` is the time since the source frame (always 0 when forward-filling).
+fn fill_gaps(
+    is_set: &[bool],
+    times: &[f32],
+    boundary_frames: &[usize],
+    options: Option<GapFillOptions>,
+    mut fill: impl FnMut(usize, usize, f32),
+) -> (Vec<bool>, GapStats) {
+    let mut is_filled = vec![false; is_set.len()];
+    let mut stats = GapStats::default();
+
+    for gap in find_gaps(is_set) {
+        let gap_frames = gap.len();
+        stats.missing_frames += gap_frames;
+        stats.gap_count += 1;
+        stats.longest_gap_frames = stats.longest_gap_frames.max(gap_frames);
+
+        let options = match options {
+            Some(_options) if gap_frames <= _options.max_gap_frames => _options,
+            _ => continue,
+        };
+        // The car (or ball) may have been respawned between the known frames.
+        if boundary_frames
+            .iter()
+            .any(|frame_number| gap.start <= *frame_number && *frame_number <= gap.end)
+        {
+            continue;
+        }
+
+        let source_frame_number = gap.start - 1;
+        for frame_number in gap {
+            let elapsed = match options.method {
+                GapFillMethod::ForwardFill => 0.0,
+                GapFillMethod::Extrapolate => times[frame_number] - times[source_frame_number],
+            };
+            fill(source_frame_number, frame_number, elapsed);
+            is_filled[frame_number] = true;
+            stats.filled_frames += 1;
+        }
+    }
+    (is_filled, stats)
+}
+
+/// Ranges of frames without data, between frames with data.
+fn find_gaps(is_set: &[bool]) -> Vec<Range<usize>> {
+    let mut gaps = vec![];
+    let mut last_set_frame_number: Option<usize> = None;
+    for (frame_number, _is_set) in is_set.iter().enumerate() {
+        if *_is_set {
+            if let Some(_last_set_frame_number) = last_set_frame_number {
+                if frame_number > _last_set_frame_number + 1 {
+                    gaps.push((_last_set_frame_number + 1)..frame_number);
+                }
+            }
+            last_set_frame_number = Some(frame_number);
+        }
+    }
+    gaps
+}
+
+fn extrapolate_position(position: Option<f32>, velocity: Option<f32>, elapsed: f32) -> Option<f32> {
+    match (position, velocity) {
+        (Some(_position), Some(_velocity)) => Some(_position + _velocity * elapsed),
+        _ => position,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::frame_parser::TimeSeriesReplayData;
+
+    fn options(max_gap_frames: usize) -> Option<GapFillOptions> {
+        Some(GapFillOptions {
+            method: GapFillMethod::Extrapolate,
+            max_gap_frames,
+        })
+    }
+
+    fn times(frame_count: usize) -> Vec<f32> {
+        (0..frame_count)
+            .map(|frame_number| frame_number as f32 * 0.1)
+            .collect()
+    }
+
+    #[test]
+    fn finds_gaps_between_set_frames_only() {
+        let is_set = [false, false, true, false, false, true, true, false];
+        assert_eq!(find_gaps(&is_set), vec![3..5]);
+    }
+
+    #[test]
+    fn leading_and_trailing_gaps_are_not_filled() {
+        let is_set = [false, true, true, false];
+        let mut filled_frames = vec![];
+        let (is_filled, stats) = fill_gaps(
+            &is_set,
+            &times(4),
+            &[],
+            options(10),
+            |_, frame_number, _| filled_frames.push(frame_number),
+        );
+        assert!(filled_frames.is_empty());
+        assert_eq!(is_filled, vec![false; 4]);
+        assert_eq!(stats, GapStats::default());
+    }
+
+    #[test]
+    fn gaps_up_to_max_gap_frames_are_filled() {
+        // Gaps of 2 and 3 frames.
+        let is_set = [true, false, false, true, false, false, false, true];
+        let mut fills = vec![];
+        let (is_filled, stats) = fill_gaps(
+            &is_set,
+            &times(8),
+            &[],
+            options(2),
+            |source_frame_number, frame_number, elapsed| {
+                fills.push((source_frame_number, frame_number, elapsed))
+            },
+        );
+        assert_eq!(fills.len(), 2);
+        assert_eq!((fills[0].0, fills[0].1), (0, 1));
+        assert!((fills[1].2 - 0.2).abs() < 1e-5);
+        assert_eq!(
+            is_filled,
+            vec![false, true, true, false, false, false, false, false]
+        );
+        assert_eq!(
+            stats,
+            GapStats {
+                missing_frames: 5,
+                gap_count: 2,
+                longest_gap_frames: 3,
+                filled_frames: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn gaps_are_not_filled_without_options_or_across_boundaries() {
+        let is_set = [true, false, false, true];
+        let (is_filled, stats) = fill_gaps(&is_set, &times(4), &[], None, |_, _, _| {});
+        assert_eq!(is_filled, vec![false; 4]);
+        assert_eq!(stats.missing_frames, 2);
+        assert_eq!(stats.filled_frames, 0);
+
+        let (is_filled, _) = fill_gaps(&is_set, &times(4), &[2], options(10), |_, _, _| {});
+        assert_eq!(is_filled, vec![false; 4]);
+    }
+
+    #[test]
+    fn data_is_only_copied_when_filled() {
+        let mut time_series_ball_data = TimeSeriesBallColumns::new(3);
+        let ball_data = TimeSeriesBallData {
+            pos_x: Some(0.0),
+            vel_x: Some(10.0),
+            ..Default::default()
+        };
+        time_series_ball_data.insert(0, ball_data);
+        time_series_ball_data.insert(2, ball_data);
+        let mut time_series_replay_data = TimeSeriesReplayColumns::new(3);
+        for (frame_number, time) in times(3).into_iter().enumerate() {
+            time_series_replay_data.insert(frame_number, TimeSeriesReplayData { time, delta: 0.1 });
+        }
+
+        let unfilled =
+            fill_ball_data_gaps(&time_series_ball_data, &time_series_replay_data, &[], None);
+        assert!(unfilled.filled_data.is_none());
+        assert_eq!(
+            unfilled.data(&time_series_ball_data),
+            &time_series_ball_data
+        );
+
+        let filled = fill_ball_data_gaps(
+            &time_series_ball_data,
+            &time_series_replay_data,
+            &[],
+            options(1),
+        );
+        let filled_ball_data = filled.data(&time_series_ball_data).get(1).unwrap();
+        assert!((filled_ball_data.pos_x.unwrap() - 1.0).abs() < 1e-5);
+    }
+}
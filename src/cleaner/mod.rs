@@ -1,7 +1,15 @@
 pub mod boost_pad;
 pub mod boost_pickup;
+pub mod game_mode;
+pub mod game_phase;
+pub mod gap_fill;
+pub mod roster;
 pub mod spawn;
 
 pub use self::boost_pad::*;
 pub use self::boost_pickup::*;
+pub use self::game_mode::*;
+pub use self::game_phase::*;
+pub use self::gap_fill::*;
+pub use self::roster::*;
 pub use self::spawn::*;
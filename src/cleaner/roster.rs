@@ -0,0 +1,191 @@
+use crate::actor_handlers::{TeamData, TimeSeriesPlayerColumns, WrappedUniqueId};
+use boxcars::ActorId;
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum RosterEventKind {
+    Joined,
+    /// Joined again after leaving, under the same unique id.
+    Rejoined,
+    Left,
+    TeamChanged,
+}
+
+/// A change to the match's roster, from the player's PRI actor.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RosterEvent {
+    pub frame_number: usize,
+    pub time: f32,
+    pub unique_id: WrappedUniqueId,
+    pub kind: RosterEventKind,
+    /// The player's team after the event, or None if not on a team.
+    pub is_orange: Option<bool>,
+}
+
+impl RosterEvent {
+    /// Finds joins and leaves from the frames with player data, and team changes from the player's team on each frame.
+    pub fn from_player_data(
+        wrapped_unique_id: &WrappedUniqueId,
+        time_series_player_data: &TimeSeriesPlayerColumns,
+        player_teams: &[Option<bool>],
+        times: &[f32],
+    ) -> Vec<Self> {
+        let mut roster_events = vec![];
+        let mut has_joined = false;
+        let mut last_is_set = false;
+        let mut last_is_orange = None;
+        for (frame_number, is_set) in time_series_player_data.is_set.iter().enumerate() {
+            let is_orange = player_teams[frame_number];
+            let kind = match (last_is_set, *is_set) {
+                (false, true) if has_joined => Some(RosterEventKind::Rejoined),
+                (false, true) => Some(RosterEventKind::Joined),
+                (true, false) => Some(RosterEventKind::Left),
+                (true, true) if is_orange != last_is_orange => Some(RosterEventKind::TeamChanged),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                roster_events.push(Self {
+                    frame_number,
+                    time: times[frame_number],
+                    unique_id: wrapped_unique_id.clone(),
+                    kind,
+                    is_orange: if *is_set { is_orange } else { last_is_orange },
+                });
+            }
+            if *is_set {
+                has_joined = true;
+                last_is_orange = is_orange;
+            }
+            last_is_set = *is_set;
+        }
+        roster_events
+    }
+}
+
+/// The player's team on each frame, from the team actor replicated on the player's PRI.
+/// None where the player has no PRI or is not on a team (e.g. spectating, or after leaving).
+pub fn get_player_teams(
+    time_series_player_data: &TimeSeriesPlayerColumns,
+    teams_data: &HashMap<ActorId, TeamData>,
+) -> Vec<Option<bool>> {
+    time_series_player_data
+        .team
+        .iter()
+        .map(|team_actor_id| {
+            teams_data
+                .get(&ActorId((*team_actor_id)?))
+                .map(|team_data| team_data.is_orange)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor_handlers::TimeSeriesPlayerData;
+
+    fn team_data(is_orange: bool) -> TeamData {
+        TeamData {
+            is_orange,
+            score: 0,
+            name: None,
+            club_id: None,
+            primary_color: None,
+            accent_color: None,
+        }
+    }
+
+    /// Player data on each frame, by team actor id (or None without a PRI).
+    fn player_columns(teams: &[Option<Option<i32>>]) -> TimeSeriesPlayerColumns {
+        let mut time_series_player_data = TimeSeriesPlayerColumns::new(teams.len());
+        for (frame_number, team) in teams.iter().enumerate() {
+            if let Some(team) = team {
+                time_series_player_data.insert(
+                    frame_number,
+                    TimeSeriesPlayerData {
+                        match_score: None,
+                        match_goals: None,
+                        match_assists: None,
+                        match_saves: None,
+                        match_shots: None,
+                        team: *team,
+                        ping: None,
+                    },
+                );
+            }
+        }
+        time_series_player_data
+    }
+
+    #[test]
+    fn player_teams_from_team_actors() {
+        let mut teams_data = HashMap::new();
+        teams_data.insert(ActorId(1), team_data(false));
+        teams_data.insert(ActorId(2), team_data(true));
+        let time_series_player_data = player_columns(&[
+            None,
+            Some(None),
+            Some(Some(1)),
+            Some(Some(2)),
+            Some(Some(3)),
+        ]);
+        assert_eq!(
+            get_player_teams(&time_series_player_data, &teams_data),
+            vec![None, None, Some(false), Some(true), None]
+        );
+    }
+
+    #[test]
+    fn roster_events_for_joins_leaves_and_team_changes() {
+        let wrapped_unique_id = WrappedUniqueId::Synthetic("a".to_string(), ActorId(0));
+        let time_series_player_data = player_columns(&[
+            None,
+            Some(None),
+            Some(Some(1)),
+            Some(Some(1)),
+            Some(Some(2)),
+            None,
+            None,
+            Some(Some(2)),
+        ]);
+        let player_teams = vec![
+            None,
+            None,
+            Some(false),
+            Some(false),
+            Some(true),
+            None,
+            None,
+            Some(true),
+        ];
+        let times: Vec<f32> = (0..8).map(|frame_number| frame_number as f32).collect();
+
+        let roster_events: Vec<(usize, RosterEventKind, Option<bool>)> =
+            RosterEvent::from_player_data(
+                &wrapped_unique_id,
+                &time_series_player_data,
+                &player_teams,
+                &times,
+            )
+            .iter()
+            .map(|roster_event| {
+                (
+                    roster_event.frame_number,
+                    roster_event.kind,
+                    roster_event.is_orange,
+                )
+            })
+            .collect();
+        assert_eq!(
+            roster_events,
+            vec![
+                (1, RosterEventKind::Joined, None),
+                (2, RosterEventKind::TeamChanged, Some(false)),
+                (4, RosterEventKind::TeamChanged, Some(true)),
+                (5, RosterEventKind::Left, Some(true)),
+                (7, RosterEventKind::Rejoined, Some(true)),
+            ]
+        );
+    }
+}
@@ -1,216 +1,314 @@
 use crate::actor_handlers::{
-    ActorHandler, ActorHandlerFactory, ActorHandlerPriority, DemoData, TeamData,
-    TimeSeriesBallData, TimeSeriesBoostData, TimeSeriesCarData, TimeSeriesGameEventData,
-    TimeSeriesPlayerData, WrappedUniqueId,
+    ActorAttributes, ActorHandler, ActorHandlerError, ActorHandlerFactory, ActorHandlerPriority,
+    AttributeKey, BoostPickupData, CameraSettings, CustomActorHandler, CustomOutput, DemoData,
+    PowerUpEventData, ReplayObjectIds, TeamData, TimeSeriesBallColumns, TimeSeriesBallData,
+    TimeSeriesBoostColumns, TimeSeriesBoostData, TimeSeriesCameraColumns, TimeSeriesCameraData,
+    TimeSeriesCarColumns, TimeSeriesCarComponentColumns, TimeSeriesCarComponentData,
+    TimeSeriesCarData, TimeSeriesGameEventColumns, TimeSeriesGameEventData,
+    TimeSeriesPlayerColumns, TimeSeriesPlayerData, TimeSeriesPowerUpColumns, TimeSeriesPowerUpData,
+    WrappedUniqueId,
 };
-use crate::cleaner::{BoostPickupKind, BoostPickupKindCalculationError};
-use crate::replay_properties_to_hash_map;
+use crate::cleaner::{
+    boost_pads_availability, fill_ball_data_gaps, fill_car_data_gaps, get_player_teams,
+    BoostPickup, BoostPickupDisagreement, BoostPickupKind, BoostPickupKindCalculationError,
+    GameMode, GamePhase, GapFillOptions, GapFilled, RosterEvent,
+};
+use crate::outputs::Goal;
+use crate::{replay_properties_to_hash_map, ParseOptions};
 use boxcars::{ActorId, Attribute, HeaderProp, NewActor, Replay, UpdatedAttribute};
 use indicatif::ProgressBar;
-use indicatif::ProgressIterator;
 use log::{info, warn};
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::iter::Iterator;
+use std::iter::{Enumerate, Iterator};
+use std::slice::Iter;
+use std::sync::Arc;
 use thiserror::Error;
 
 #[derive(Debug, Clone)]
 pub struct FrameParser {
     pub replay_version: i32,
     pub frame_count: usize,
+    pub game_mode: GameMode,
+    pub object_ids: Arc<ReplayObjectIds>,
+    /// Frames on which goals were scored, from the replay header.
+    pub goal_frames: Vec<usize>,
     pub car_ids_to_player_ids: RefCell<HashMap<ActorId, ActorId>>,
     pub players_wrapped_unique_id: RefCell<HashMap<ActorId, WrappedUniqueId>>,
 
-    pub players_actor: RefCell<HashMap<WrappedUniqueId, HashMap<String, Attribute>>>,
-    pub players_teams: RefCell<HashMap<WrappedUniqueId, HashMap<bool, usize>>>,
+    pub players_actor: RefCell<HashMap<WrappedUniqueId, ActorAttributes>>,
     pub teams_data: RefCell<HashMap<ActorId, TeamData>>,
 
-    pub game_info_actor: RefCell<Option<HashMap<String, Attribute>>>,
-    pub game_event_actor: RefCell<Option<HashMap<String, Attribute>>>,
-
-    pub time_series_replay_data: RefCell<HashMap<usize, TimeSeriesReplayData>>,
-    pub time_series_game_event_data: RefCell<HashMap<usize, TimeSeriesGameEventData>>,
-    pub time_series_ball_data: RefCell<HashMap<usize, TimeSeriesBallData>>,
-    pub players_time_series_car_data:
-        RefCell<HashMap<WrappedUniqueId, HashMap<usize, TimeSeriesCarData>>>,
-    pub players_time_series_player_data:
-        RefCell<HashMap<WrappedUniqueId, HashMap<usize, TimeSeriesPlayerData>>>,
-    pub players_time_series_boost_data:
-        RefCell<HashMap<WrappedUniqueId, HashMap<usize, TimeSeriesBoostData>>>,
+    pub game_info_actor: RefCell<Option<ActorAttributes>>,
+    pub game_event_actor: RefCell<Option<ActorAttributes>>,
+
+    pub time_series_replay_data: RefCell<TimeSeriesReplayColumns>,
+    pub time_series_game_event_data: RefCell<TimeSeriesGameEventColumns>,
+    pub time_series_ball_data: RefCell<TimeSeriesBallColumns>,
+    pub players_time_series_car_data: RefCell<HashMap<WrappedUniqueId, TimeSeriesCarColumns>>,
+    pub players_time_series_player_data: RefCell<HashMap<WrappedUniqueId, TimeSeriesPlayerColumns>>,
+    pub players_time_series_boost_data: RefCell<HashMap<WrappedUniqueId, TimeSeriesBoostColumns>>,
+    pub players_time_series_camera_data: RefCell<HashMap<WrappedUniqueId, TimeSeriesCameraColumns>>,
+    pub players_time_series_car_component_data:
+        RefCell<HashMap<WrappedUniqueId, TimeSeriesCarComponentColumns>>,
+    pub players_time_series_power_up_data:
+        RefCell<HashMap<WrappedUniqueId, TimeSeriesPowerUpColumns>>,
+    pub players_camera_settings: RefCell<HashMap<WrappedUniqueId, CameraSettings>>,
     pub demos_data: RefCell<Vec<DemoData>>,
+    pub boost_pickups_data: RefCell<Vec<BoostPickupData>>,
+    pub power_up_events_data: RefCell<Vec<PowerUpEventData>>,
+
+    /// Data written by actor handlers for the frame currently being processed.
+    pub current_frame_snapshot: RefCell<FrameSnapshot>,
+
+    pub custom_actor_handlers: Vec<CustomActorHandler>,
+    /// Outputs of custom actor handlers, keyed by handler name.
+    pub custom_outputs: RefCell<HashMap<String, Box<dyn CustomOutput>>>,
 
-    pub cleaned_data: Option<CleanedData>,
+    /// How gaps in car and ball data are filled in `clean_up`, if at all.
+    pub gap_fill_options: Option<GapFillOptions>,
 }
 
 impl FrameParser {
-    pub fn from_replay(replay: &Replay, show_progress: bool) -> Result<Self, FrameParserError> {
-        let mut frame_parser = Self::new(replay);
-        frame_parser.process_replay(replay, show_progress)?;
-        frame_parser
-            .clean_up()
-            .map_err(FrameParserError::CleanUpError)?;
-        Ok(frame_parser)
-    }
-
-    pub fn new(replay: &Replay) -> Self {
+    pub fn new(replay: &Replay) -> Result<Self, FrameParserError> {
         let properties = replay_properties_to_hash_map(replay);
         let replay_version = match properties.get("ReplayVersion") {
             Some(HeaderProp::Int(replay_version)) => replay_version,
-            _ => panic!("Cannot parse replay version from header properties."),
+            _ => return Err(FrameParserError::MissingReplayVersion),
+        };
+        let map_name = match properties.get("MapName") {
+            Some(HeaderProp::Name(map_name)) => Some(map_name.as_str()),
+            _ => None,
         };
         match &replay.network_frames {
             Some(network_frames) => {
                 let frame_count = network_frames.frames.len();
                 info!("Replay with {} frames", frame_count);
 
-                Self {
+                Ok(Self {
                     replay_version: *replay_version,
                     frame_count,
+                    game_mode: GameMode::from_replay(replay, map_name),
+                    object_ids: Arc::new(ReplayObjectIds::new(&replay.objects)),
+                    goal_frames: Goal::from_replay_properties(&properties)
+                        .iter()
+                        .map(|goal| goal.frame as usize)
+                        .collect(),
                     car_ids_to_player_ids: RefCell::new(HashMap::new()),
                     players_wrapped_unique_id: RefCell::new(HashMap::new()),
 
                     teams_data: RefCell::new(HashMap::new()),
                     players_actor: RefCell::new(HashMap::new()),
-                    players_teams: RefCell::new(HashMap::new()),
                     game_info_actor: RefCell::new(None),
                     game_event_actor: RefCell::new(None),
 
-                    time_series_replay_data: RefCell::new(HashMap::with_capacity(frame_count)),
-                    time_series_game_event_data: RefCell::new(HashMap::with_capacity(frame_count)),
-                    time_series_ball_data: RefCell::new(HashMap::with_capacity(frame_count)),
+                    // Columns are allocated in process_replay, as streamed frames are not stored.
+                    time_series_replay_data: RefCell::new(TimeSeriesReplayColumns::new(0)),
+                    time_series_game_event_data: RefCell::new(TimeSeriesGameEventColumns::new(0)),
+                    time_series_ball_data: RefCell::new(TimeSeriesBallColumns::new(0)),
                     players_time_series_car_data: RefCell::new(HashMap::new()),
                     players_time_series_player_data: RefCell::new(HashMap::new()),
                     players_time_series_boost_data: RefCell::new(HashMap::new()),
+                    players_time_series_camera_data: RefCell::new(HashMap::new()),
+                    players_time_series_car_component_data: RefCell::new(HashMap::new()),
+                    players_time_series_power_up_data: RefCell::new(HashMap::new()),
+                    players_camera_settings: RefCell::new(HashMap::new()),
                     demos_data: RefCell::new(vec![]),
+                    boost_pickups_data: RefCell::new(vec![]),
+                    power_up_events_data: RefCell::new(vec![]),
 
-                    cleaned_data: None,
-                }
-            }
-            None => {
-                panic!("No network frames.")
+                    current_frame_snapshot: RefCell::new(FrameSnapshot::new(0, 0.0, 0.0)),
+
+                    custom_actor_handlers: vec![],
+                    custom_outputs: RefCell::new(HashMap::new()),
+
+                    gap_fill_options: None,
+                })
             }
+            None => Err(FrameParserError::MissingNetworkFrames),
         }
     }
 
+    /// Registers a custom handler for actors not handled by the built-in handlers.
+    /// Must be called before the replay is processed.
+    pub fn register_actor_handler(&mut self, custom_actor_handler: CustomActorHandler) {
+        self.custom_outputs
+            .borrow_mut()
+            .entry(custom_actor_handler.name.clone())
+            .or_insert_with(|| custom_actor_handler.output.clone());
+        self.custom_actor_handlers.push(custom_actor_handler);
+    }
+
+    /// Runs `f` on the named custom handler output, if it exists and is of type `T`.
+    /// Used by custom handlers to write their output.
+    pub fn with_custom_output<T: CustomOutput, R>(
+        &self,
+        name: &str,
+        f: impl FnOnce(&mut T) -> R,
+    ) -> Option<R> {
+        let mut custom_outputs = self.custom_outputs.borrow_mut();
+        let custom_output = custom_outputs.get_mut(name)?;
+        (**custom_output).as_any_mut().downcast_mut::<T>().map(f)
+    }
+
     pub fn process_replay(
         &self,
         replay: &Replay,
         show_progress: bool,
     ) -> Result<(), FrameParserError> {
-        let network_frames = replay
-            .network_frames
-            .as_ref()
-            .ok_or(FrameParserError::MissingNetworkFrames)?;
-
-        let handler_factory = ActorHandlerFactory::new(self);
-        let mut actor_handlers: HashMap<
-            ActorHandlerPriority,
-            HashMap<ActorId, Box<dyn ActorHandler>>,
-        > = HashMap::new();
-        let mut actors: HashMap<ActorId, Actor> = HashMap::new();
-        let replay_objects = &replay.objects;
-
-        let mut time_series_replay_data = self.time_series_replay_data.borrow_mut();
-
-        let iter: Box<dyn Iterator<Item = (usize, &boxcars::Frame)>> = if show_progress {
-            let progress_bar = ProgressBar::new(self.frame_count as u64);
-            progress_bar.set_draw_rate(30);
-            Box::new(
-                network_frames
-                    .frames
-                    .iter()
-                    .enumerate()
-                    .progress_with(progress_bar),
-            )
-        } else {
-            Box::new(network_frames.frames.iter().enumerate())
-        };
-        for (frame_number, frame) in iter.into_iter() {
-            let time = frame.time;
-            let delta = frame.delta;
-            // info!("### Frame {} ({}, {})", frame_number, time, delta);
-
-            // Handle deleted actors first
-            for deleted_actor_id in &frame.deleted_actors {
-                if actors.remove(deleted_actor_id).is_none() {
-                    warn!(
-                        "Could not find actor {} to delete on frame {}.",
-                        deleted_actor_id, frame_number
-                    );
-                }
-                if let Some(_actor_handlers) = actor_handlers.get_mut(&ActorHandlerPriority::First)
-                {
-                    _actor_handlers.remove(deleted_actor_id);
-                }
-                if let Some(_actor_handlers) =
-                    actor_handlers.get_mut(&ActorHandlerPriority::Standard)
-                {
-                    _actor_handlers.remove(deleted_actor_id);
-                }
-            }
-
-            // Handle new actors
-            for new_actor in &frame.new_actors {
-                let actor_id = new_actor.actor_id;
-                actors.insert(actor_id, Actor::new(new_actor));
-                if let Some(handler) =
-                    handler_factory.get_handler(new_actor.object_id, replay_objects)
-                {
-                    let _actor_handlers = actor_handlers
-                        .entry(handler.priority())
-                        .or_insert_with(HashMap::new);
-                    _actor_handlers.insert(actor_id, handler);
-                }
-            }
+        self.time_series_replay_data
+            .replace(TimeSeriesReplayColumns::new(self.frame_count));
+        self.time_series_game_event_data
+            .replace(TimeSeriesGameEventColumns::new(self.frame_count));
+        self.time_series_ball_data
+            .replace(TimeSeriesBallColumns::new(self.frame_count));
 
-            // Handle updated actors
-            for updated_attribute in &frame.updated_actors {
-                let actor_id = updated_attribute.actor_id;
-                let actor = actors
-                    .get_mut(&actor_id)
-                    .expect("Updated actor does not exist.");
-                actor.update_attribute(updated_attribute, replay_objects);
-            }
+        let frame_iterator = FrameIterator::new(self, replay, show_progress, true)?;
+        for frame_snapshot in frame_iterator {
+            frame_snapshot?;
+        }
+        Ok(())
+    }
 
-            // Stop data collection after goal
-            // if previous_goal_frame_number.is_some()
-            //     && frame_number > previous_goal_frame_number.unwrap()
-            // {
-            //     // TODO: Set players to sleeping
-            //     // https://github.com/SaltieRL/carball/blob/f9e4854e173bb6db3e53cc93ac1daa4e58952e69/carball/json_parser/frame_parser.py#L189
-            // }
+    /// Streams per-frame snapshots as the network frames are processed.
+    /// Unlike `process_replay`, time-series data is not stored in the FrameParser, so snapshots should be consumed as they are yielded.
+    pub fn frames<'a>(
+        &'a self,
+        replay: &'a Replay,
+        show_progress: bool,
+    ) -> Result<FrameIterator<'a>, FrameParserError> {
+        FrameIterator::new(self, replay, show_progress, false)
+    }
 
-            // Run handler updates
-            for priority in ActorHandlerPriority::iterator() {
-                if let Some(mut _actor_handlers) = actor_handlers.get_mut(priority) {
-                    for (actor_id, handler) in _actor_handlers.iter_mut() {
-                        handler.update(
-                            actors.get(actor_id).ok_or_else(|| {
-                                FrameParserError::ActorUpdateMissingIdError(frame_number, *actor_id)
-                            })?,
-                            frame_number,
-                            time,
-                            delta,
-                        )
-                    }
-                }
-            }
-            time_series_replay_data.insert(frame_number, TimeSeriesReplayData { time, delta });
+    fn store_frame_snapshot(&self, frame_snapshot: &FrameSnapshot) {
+        let frame_number = frame_snapshot.frame_number;
+        self.time_series_replay_data
+            .borrow_mut()
+            .insert(frame_number, frame_snapshot.replay_data);
+        if let Some(game_event_data) = frame_snapshot.game_event_data {
+            self.time_series_game_event_data
+                .borrow_mut()
+                .insert(frame_number, game_event_data);
+        }
+        if let Some(ball_data) = frame_snapshot.ball_data {
+            self.time_series_ball_data
+                .borrow_mut()
+                .insert(frame_number, ball_data);
         }
 
-        Ok(())
+        let mut players_time_series_car_data = self.players_time_series_car_data.borrow_mut();
+        for (wrapped_unique_id, car_data) in frame_snapshot.players_car_data.iter() {
+            players_time_series_car_data
+                .entry(wrapped_unique_id.clone())
+                .or_insert_with(|| TimeSeriesCarColumns::new(self.frame_count))
+                .insert(frame_number, *car_data);
+        }
+        let mut players_time_series_player_data = self.players_time_series_player_data.borrow_mut();
+        for (wrapped_unique_id, player_data) in frame_snapshot.players_player_data.iter() {
+            players_time_series_player_data
+                .entry(wrapped_unique_id.clone())
+                .or_insert_with(|| TimeSeriesPlayerColumns::new(self.frame_count))
+                .insert(frame_number, player_data.clone());
+        }
+        let mut players_time_series_boost_data = self.players_time_series_boost_data.borrow_mut();
+        for (wrapped_unique_id, boost_data) in frame_snapshot.players_boost_data.iter() {
+            players_time_series_boost_data
+                .entry(wrapped_unique_id.clone())
+                .or_insert_with(|| TimeSeriesBoostColumns::new(self.frame_count))
+                .insert(frame_number, *boost_data);
+        }
+        let mut players_time_series_camera_data = self.players_time_series_camera_data.borrow_mut();
+        for (wrapped_unique_id, camera_data) in frame_snapshot.players_camera_data.iter() {
+            players_time_series_camera_data
+                .entry(wrapped_unique_id.clone())
+                .or_insert_with(|| TimeSeriesCameraColumns::new(self.frame_count))
+                .insert(frame_number, *camera_data);
+        }
+        let mut players_time_series_car_component_data =
+            self.players_time_series_car_component_data.borrow_mut();
+        for (wrapped_unique_id, car_component_data) in
+            frame_snapshot.players_car_component_data.iter()
+        {
+            players_time_series_car_component_data
+                .entry(wrapped_unique_id.clone())
+                .or_insert_with(|| TimeSeriesCarComponentColumns::new(self.frame_count))
+                .insert(frame_number, *car_component_data);
+        }
+        let mut players_time_series_power_up_data =
+            self.players_time_series_power_up_data.borrow_mut();
+        for (wrapped_unique_id, power_up_data) in frame_snapshot.players_power_up_data.iter() {
+            players_time_series_power_up_data
+                .entry(wrapped_unique_id.clone())
+                .or_insert_with(|| TimeSeriesPowerUpColumns::new(self.frame_count))
+                .insert(frame_number, *power_up_data);
+        }
     }
 
-    pub fn clean_up(&mut self) -> Result<(), BoostPickupKindCalculationError> {
+    pub fn clean_up(&self) -> Result<CleanedData, BoostPickupKindCalculationError> {
         let players_actor = self.players_actor.borrow();
 
         let players_time_series_car_data = self.players_time_series_car_data.borrow();
         let players_time_series_boost_data = self.players_time_series_boost_data.borrow();
 
         let mut cleaned_data = CleanedData::new();
+        cleaned_data.game_phases = GamePhase::from_game_event_data(
+            &self.time_series_game_event_data.borrow(),
+            &self.goal_frames,
+            self.frame_count,
+        );
+
+        let time_series_replay_data = self.time_series_replay_data.borrow();
+        let demos_data = self.demos_data.borrow();
+        cleaned_data.time_series_ball_data = fill_ball_data_gaps(
+            &self.time_series_ball_data.borrow(),
+            &time_series_replay_data,
+            &self.goal_frames,
+            self.gap_fill_options,
+        );
+        for (wrapped_unique_id, time_series_car_data) in players_time_series_car_data.iter() {
+            // Cars respawn after being demolished and on kickoffs after goals.
+            let mut boundary_frames: Vec<usize> = demos_data
+                .iter()
+                .filter(|demo_data| &demo_data.victim_wrapped_unique_id == wrapped_unique_id)
+                .map(|demo_data| demo_data.frame_number)
+                .collect();
+            boundary_frames.extend(self.goal_frames.iter());
+            cleaned_data.players_time_series_car_data.insert(
+                wrapped_unique_id.clone(),
+                fill_car_data_gaps(
+                    time_series_car_data,
+                    &time_series_replay_data,
+                    &boundary_frames,
+                    self.gap_fill_options,
+                ),
+            );
+        }
+
+        let teams_data = self.teams_data.borrow();
+        for (wrapped_unique_id, time_series_player_data) in
+            self.players_time_series_player_data.borrow().iter()
+        {
+            let player_teams = get_player_teams(time_series_player_data, &teams_data);
+            cleaned_data
+                .roster_events
+                .extend(RosterEvent::from_player_data(
+                    wrapped_unique_id,
+                    time_series_player_data,
+                    &player_teams,
+                    &time_series_replay_data.time,
+                ));
+            cleaned_data
+                .players_time_series_team
+                .insert(wrapped_unique_id.clone(), player_teams);
+        }
+        cleaned_data.roster_events.sort_by(|a, b| {
+            a.frame_number
+                .cmp(&b.frame_number)
+                .then_with(|| a.unique_id.to_string().cmp(&b.unique_id.to_string()))
+        });
 
         for (wrapped_unique_id, player_actor) in players_actor.iter() {
-            let player_name = match player_actor.get("Engine.PlayerReplicationInfo:PlayerName") {
+            let player_name = match player_actor.get(AttributeKey::PlayerName) {
                 Some(Attribute::String(_player_name)) => _player_name,
                 _ => "UnknownName",
             };
@@ -226,44 +324,42 @@ impl FrameParser {
                     players_time_series_car_data.get(wrapped_unique_id)
                 {
                     let mut cleaned_time_series_boost_data =
-                        HashMap::with_capacity(self.frame_count);
-                    let mut cleaned_time_series_boost_pickup_data =
-                        HashMap::with_capacity(self.frame_count);
+                        TimeSeriesBoostColumns::new(self.frame_count);
+                    let mut cleaned_time_series_boost_pickup_data = vec![None; self.frame_count];
 
                     let mut last_raw_boost_amount: f32 = 0.0;
                     let mut last_predicted_boost_amount: f32 = 0.0;
                     let mut delta_boost_is_active_since_last_update: f32 = 0.0;
+                    // Indexes several per-frame columns, not just the pickup column.
+                    #[allow(clippy::needless_range_loop)]
                     for frame_number in 0..(self.frame_count - 1) {
-                        if let Some(boost_data) = time_series_boost_data.get(&frame_number) {
-                            let delta = self
-                                .time_series_replay_data
-                                .borrow()
-                                .get(&frame_number)
-                                .unwrap()
-                                .delta;
+                        if let Some(boost_data) = time_series_boost_data.get(frame_number) {
+                            let delta = time_series_replay_data.delta[frame_number];
                             let new_raw_boost_amount = boost_data.boost_amount.unwrap_or(0.0);
                             if (last_raw_boost_amount - new_raw_boost_amount).abs() > f32::EPSILON {
                                 // Boost amount updated in replay file
                                 // Detect boost pickup
                                 if new_raw_boost_amount > last_predicted_boost_amount {
-                                    cleaned_time_series_boost_pickup_data.insert(
-                                        frame_number,
-                                        BoostPickupKind::detect_boost_pickup_kind(
-                                            last_predicted_boost_amount,
-                                            new_raw_boost_amount,
-                                            time_series_car_data.get(&frame_number),
-                                            frame_number,
-                                            player_name,
-                                            self.replay_version,
-                                        )?,
-                                    );
+                                    cleaned_time_series_boost_pickup_data[frame_number] =
+                                        Some(BoostPickupKind::to_column_value(
+                                            BoostPickupKind::detect_boost_pickup_kind(
+                                                last_predicted_boost_amount,
+                                                new_raw_boost_amount,
+                                                time_series_car_data.get(frame_number).as_ref(),
+                                                frame_number,
+                                                player_name,
+                                                self.replay_version,
+                                                self.game_mode,
+                                            )?,
+                                        ));
                                 }
 
                                 // Reset delta counter as boost amount is up-to-date and accurate.
                                 delta_boost_is_active_since_last_update = 0.0;
                                 last_raw_boost_amount = new_raw_boost_amount;
                             } else {
-                                cleaned_time_series_boost_pickup_data.insert(frame_number, None);
+                                cleaned_time_series_boost_pickup_data[frame_number] =
+                                    Some(BoostPickupKind::to_column_value(None));
                             }
                             if boost_data.boost_is_active.unwrap_or(false) {
                                 if delta_boost_is_active_since_last_update == 0.0 {
@@ -278,13 +374,13 @@ impl FrameParser {
                                     - (33.3 * delta_boost_is_active_since_last_update);
                                 last_predicted_boost_amount = predicted_boost_amount;
 
-                                let mut modified_boost_data = *boost_data;
+                                let mut modified_boost_data = boost_data;
                                 modified_boost_data.boost_amount =
                                     Some(predicted_boost_amount.clamp(0.0, 100.0));
                                 cleaned_time_series_boost_data
                                     .insert(frame_number, modified_boost_data);
                             } else {
-                                cleaned_time_series_boost_data.insert(frame_number, *boost_data);
+                                cleaned_time_series_boost_data.insert(frame_number, boost_data);
                                 last_predicted_boost_amount = new_raw_boost_amount;
                             }
                         }
@@ -299,57 +395,427 @@ impl FrameParser {
                 }
             }
         }
-        self.cleaned_data = Some(cleaned_data);
-        Ok(())
+
+        cleaned_data.boost_pickups = BoostPickup::from_boost_pickups_data(
+            &self.boost_pickups_data.borrow(),
+            &players_time_series_car_data,
+            self.game_mode,
+        );
+        cleaned_data.boost_pickup_disagreements = BoostPickupDisagreement::reconcile(
+            &cleaned_data.boost_pickups,
+            &cleaned_data.players_time_series_boost_pickup_data,
+        );
+        if self.game_mode.uses_soccar_arena() {
+            cleaned_data.boost_pads_availability = boost_pads_availability(
+                &cleaned_data.boost_pickups,
+                &time_series_replay_data.time,
+                &self.goal_frames,
+            );
+        }
+        if !cleaned_data.boost_pickup_disagreements.is_empty() {
+            warn!(
+                "{} disagreements between replicated and inferred boost pickups ({} replicated pickups).",
+                cleaned_data.boost_pickup_disagreements.len(),
+                cleaned_data.boost_pickups.len()
+            );
+        }
+        Ok(cleaned_data)
+    }
+
+    /// Cleans up the processed data and moves it out of the FrameParser.
+    pub fn into_parsed_replay(self) -> Result<ParsedReplay, FrameParserError> {
+        let cleaned_data = self.clean_up().map_err(FrameParserError::CleanUpError)?;
+        Ok(ParsedReplay {
+            replay_version: self.replay_version,
+            frame_count: self.frame_count,
+            game_mode: self.game_mode,
+            players_actor: self.players_actor.into_inner(),
+            teams_data: self.teams_data.into_inner(),
+            game_info_actor: self.game_info_actor.into_inner(),
+            game_event_actor: self.game_event_actor.into_inner(),
+            time_series_replay_data: self.time_series_replay_data.into_inner(),
+            time_series_game_event_data: self.time_series_game_event_data.into_inner(),
+            time_series_ball_data: self.time_series_ball_data.into_inner(),
+            players_time_series_car_data: self.players_time_series_car_data.into_inner(),
+            players_time_series_player_data: self.players_time_series_player_data.into_inner(),
+            players_time_series_boost_data: self.players_time_series_boost_data.into_inner(),
+            players_time_series_camera_data: self.players_time_series_camera_data.into_inner(),
+            players_time_series_car_component_data: self
+                .players_time_series_car_component_data
+                .into_inner(),
+            players_time_series_power_up_data: self.players_time_series_power_up_data.into_inner(),
+            players_camera_settings: self.players_camera_settings.into_inner(),
+            demos_data: self.demos_data.into_inner(),
+            boost_pickups_data: self.boost_pickups_data.into_inner(),
+            power_up_events_data: self.power_up_events_data.into_inner(),
+            custom_outputs: self.custom_outputs.into_inner(),
+            cleaned_data,
+        })
+    }
+}
+
+/// The data parsed from a replay's network frames.
+/// Unlike FrameParser, this has no interior mutability, so it can be shared across threads.
+#[derive(Debug, Clone)]
+pub struct ParsedReplay {
+    pub replay_version: i32,
+    pub frame_count: usize,
+    pub game_mode: GameMode,
+
+    pub players_actor: HashMap<WrappedUniqueId, ActorAttributes>,
+    pub teams_data: HashMap<ActorId, TeamData>,
+
+    pub game_info_actor: Option<ActorAttributes>,
+    pub game_event_actor: Option<ActorAttributes>,
+
+    pub time_series_replay_data: TimeSeriesReplayColumns,
+    pub time_series_game_event_data: TimeSeriesGameEventColumns,
+    pub time_series_ball_data: TimeSeriesBallColumns,
+    pub players_time_series_car_data: HashMap<WrappedUniqueId, TimeSeriesCarColumns>,
+    pub players_time_series_player_data: HashMap<WrappedUniqueId, TimeSeriesPlayerColumns>,
+    pub players_time_series_boost_data: HashMap<WrappedUniqueId, TimeSeriesBoostColumns>,
+    pub players_time_series_camera_data: HashMap<WrappedUniqueId, TimeSeriesCameraColumns>,
+    pub players_time_series_car_component_data:
+        HashMap<WrappedUniqueId, TimeSeriesCarComponentColumns>,
+    pub players_time_series_power_up_data: HashMap<WrappedUniqueId, TimeSeriesPowerUpColumns>,
+    pub players_camera_settings: HashMap<WrappedUniqueId, CameraSettings>,
+    pub demos_data: Vec<DemoData>,
+    pub boost_pickups_data: Vec<BoostPickupData>,
+    pub power_up_events_data: Vec<PowerUpEventData>,
+
+    /// Outputs of custom actor handlers, keyed by handler name.
+    pub custom_outputs: HashMap<String, Box<dyn CustomOutput>>,
+
+    pub cleaned_data: CleanedData,
+}
+
+impl ParsedReplay {
+    pub fn from_replay(replay: &Replay, show_progress: bool) -> Result<Self, FrameParserError> {
+        Self::from_replay_with_options(
+            replay,
+            &ParseOptions {
+                show_progress,
+                ..ParseOptions::default()
+            },
+        )
+    }
+
+    /// Parses the replay's network frames, additionally running the options' custom actor handlers.
+    pub fn from_replay_with_options(
+        replay: &Replay,
+        options: &ParseOptions,
+    ) -> Result<Self, FrameParserError> {
+        let mut frame_parser = FrameParser::new(replay)?;
+        frame_parser.gap_fill_options = options.gap_fill;
+        for custom_actor_handler in options.custom_actor_handlers.iter() {
+            frame_parser.register_actor_handler(custom_actor_handler.clone());
+        }
+        frame_parser.process_replay(replay, options.show_progress)?;
+        frame_parser.into_parsed_replay()
+    }
+
+    /// Returns the named custom handler output, if it exists and is of type `T`.
+    pub fn custom_output<T: CustomOutput>(&self, name: &str) -> Option<&T> {
+        let custom_output = self.custom_outputs.get(name)?;
+        (**custom_output).as_any().downcast_ref::<T>()
+    }
+}
+
+/// Iterates over the replay's network frames, running actor handlers on each frame and yielding a `FrameSnapshot` of the handled data.
+pub struct FrameIterator<'a> {
+    frame_parser: &'a FrameParser,
+    replay_objects: &'a [String],
+    frames: Enumerate<Iter<'a, boxcars::Frame>>,
+    handler_factory: ActorHandlerFactory<'a>,
+    actor_handlers: HashMap<ActorHandlerPriority, HashMap<ActorId, Box<dyn ActorHandler<'a> + 'a>>>,
+    custom_actor_handlers: HashMap<ActorId, Vec<Box<dyn ActorHandler<'a> + 'a>>>,
+    actors: HashMap<ActorId, Actor<'a>>,
+    progress_bar: Option<ProgressBar>,
+    store_time_series: bool,
+}
+
+impl<'a> FrameIterator<'a> {
+    pub fn new(
+        frame_parser: &'a FrameParser,
+        replay: &'a Replay,
+        show_progress: bool,
+        store_time_series: bool,
+    ) -> Result<Self, FrameParserError> {
+        let network_frames = replay
+            .network_frames
+            .as_ref()
+            .ok_or(FrameParserError::MissingNetworkFrames)?;
+
+        let progress_bar = if show_progress {
+            let progress_bar = ProgressBar::new(frame_parser.frame_count as u64);
+            progress_bar.set_draw_rate(30);
+            Some(progress_bar)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            frame_parser,
+            replay_objects: &replay.objects,
+            frames: network_frames.frames.iter().enumerate(),
+            handler_factory: ActorHandlerFactory::new(frame_parser),
+            actor_handlers: HashMap::new(),
+            custom_actor_handlers: HashMap::new(),
+            actors: HashMap::new(),
+            progress_bar,
+            store_time_series,
+        })
+    }
+
+    fn process_frame(
+        &mut self,
+        frame_number: usize,
+        frame: &'a boxcars::Frame,
+    ) -> Result<FrameSnapshot, FrameParserError> {
+        let time = frame.time;
+        let delta = frame.delta;
+        // info!("### Frame {} ({}, {})", frame_number, time, delta);
+        self.frame_parser
+            .current_frame_snapshot
+            .replace(FrameSnapshot::new(frame_number, time, delta));
+
+        // Handle deleted actors first
+        for deleted_actor_id in &frame.deleted_actors {
+            let deleted_actor = match self.actors.remove(deleted_actor_id) {
+                Some(deleted_actor) => deleted_actor,
+                None => {
+                    warn!(
+                        "Could not find actor {} to delete on frame {}.",
+                        deleted_actor_id, frame_number
+                    );
+                    continue;
+                }
+            };
+            let mut deleted_handlers: Vec<Box<dyn ActorHandler<'a> + 'a>> = vec![];
+            for _actor_handlers in self.actor_handlers.values_mut() {
+                deleted_handlers.extend(_actor_handlers.remove(deleted_actor_id));
+            }
+            deleted_handlers.extend(
+                self.custom_actor_handlers
+                    .remove(deleted_actor_id)
+                    .unwrap_or_default(),
+            );
+            for mut handler in deleted_handlers {
+                handler
+                    .delete(&deleted_actor, frame_number)
+                    .map_err(|err| {
+                        FrameParserError::ActorHandlerError(frame_number, *deleted_actor_id, err)
+                    })?;
+            }
+        }
+
+        // Handle new actors
+        for new_actor in &frame.new_actors {
+            let actor_id = new_actor.actor_id;
+            self.actors.insert(
+                actor_id,
+                Actor::new(new_actor, self.frame_parser.object_ids.clone()),
+            );
+            if let Some(handler) = self
+                .handler_factory
+                .get_handler(new_actor.object_id, self.replay_objects)
+            {
+                let _actor_handlers = self.actor_handlers.entry(handler.priority()).or_default();
+                _actor_handlers.insert(actor_id, handler);
+            }
+            let custom_handlers = self
+                .handler_factory
+                .get_custom_handlers(new_actor.object_id, self.replay_objects);
+            if !custom_handlers.is_empty() {
+                self.custom_actor_handlers.insert(actor_id, custom_handlers);
+            }
+        }
+
+        // Handle updated actors
+        for updated_attribute in &frame.updated_actors {
+            let actor_id = updated_attribute.actor_id;
+            let actor = self.actors.get_mut(&actor_id).ok_or(
+                FrameParserError::ActorUpdateMissingIdError(frame_number, actor_id),
+            )?;
+            actor.update_attribute(updated_attribute);
+        }
+
+        // Run handler updates
+        for priority in ActorHandlerPriority::iterator() {
+            if let Some(_actor_handlers) = self.actor_handlers.get_mut(priority) {
+                for (actor_id, handler) in _actor_handlers.iter_mut() {
+                    handler
+                        .update(
+                            self.actors.get(actor_id).ok_or(
+                                FrameParserError::ActorUpdateMissingIdError(
+                                    frame_number,
+                                    *actor_id,
+                                ),
+                            )?,
+                            frame_number,
+                            time,
+                            delta,
+                        )
+                        .map_err(|err| {
+                            FrameParserError::ActorHandlerError(frame_number, *actor_id, err)
+                        })?;
+                }
+            }
+        }
+        for (actor_id, handlers) in self.custom_actor_handlers.iter_mut() {
+            let actor =
+                self.actors
+                    .get(actor_id)
+                    .ok_or(FrameParserError::ActorUpdateMissingIdError(
+                        frame_number,
+                        *actor_id,
+                    ))?;
+            for handler in handlers.iter_mut() {
+                handler
+                    .update(actor, frame_number, time, delta)
+                    .map_err(|err| {
+                        FrameParserError::ActorHandlerError(frame_number, *actor_id, err)
+                    })?;
+            }
+        }
+
+        let frame_snapshot = self
+            .frame_parser
+            .current_frame_snapshot
+            .replace(FrameSnapshot::new(frame_number, time, delta));
+        if self.store_time_series {
+            self.frame_parser.store_frame_snapshot(&frame_snapshot);
+        }
+        Ok(frame_snapshot)
+    }
+}
+
+impl<'a> Iterator for FrameIterator<'a> {
+    type Item = Result<FrameSnapshot, FrameParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.frames.next() {
+            Some((frame_number, frame)) => {
+                if let Some(progress_bar) = &self.progress_bar {
+                    progress_bar.inc(1);
+                }
+                Some(self.process_frame(frame_number, frame))
+            }
+            None => {
+                if let Some(progress_bar) = self.progress_bar.take() {
+                    progress_bar.finish();
+                }
+                None
+            }
+        }
+    }
+}
+
+/// Data extracted by the actor handlers on a single frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrameSnapshot {
+    pub frame_number: usize,
+    pub replay_data: TimeSeriesReplayData,
+    pub game_event_data: Option<TimeSeriesGameEventData>,
+    pub ball_data: Option<TimeSeriesBallData>,
+    pub players_car_data: HashMap<WrappedUniqueId, TimeSeriesCarData>,
+    pub players_player_data: HashMap<WrappedUniqueId, TimeSeriesPlayerData>,
+    pub players_boost_data: HashMap<WrappedUniqueId, TimeSeriesBoostData>,
+    pub players_camera_data: HashMap<WrappedUniqueId, TimeSeriesCameraData>,
+    pub players_car_component_data: HashMap<WrappedUniqueId, TimeSeriesCarComponentData>,
+    pub players_power_up_data: HashMap<WrappedUniqueId, TimeSeriesPowerUpData>,
+}
+
+impl FrameSnapshot {
+    pub fn new(frame_number: usize, time: f32, delta: f32) -> Self {
+        Self {
+            frame_number,
+            replay_data: TimeSeriesReplayData { time, delta },
+            game_event_data: None,
+            ball_data: None,
+            players_car_data: HashMap::new(),
+            players_player_data: HashMap::new(),
+            players_boost_data: HashMap::new(),
+            players_camera_data: HashMap::new(),
+            players_car_component_data: HashMap::new(),
+            players_power_up_data: HashMap::new(),
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 pub struct Actor<'a> {
     pub new_actor: &'a NewActor,
-    pub attributes: RefCell<HashMap<String, Attribute>>,
+    pub attributes: RefCell<ActorAttributes>,
 }
 
 impl<'a> Actor<'a> {
-    fn new(new_actor: &'a NewActor) -> Self {
+    fn new(new_actor: &'a NewActor, object_ids: Arc<ReplayObjectIds>) -> Self {
         Self {
             new_actor,
-            attributes: RefCell::new(HashMap::new()),
+            attributes: RefCell::new(ActorAttributes::new(object_ids)),
         }
     }
 
-    fn update_attribute(
-        &mut self,
-        updated_attribute: &UpdatedAttribute,
-        replay_objects: &[String],
-    ) {
-        let prop_name = replay_objects[updated_attribute.object_id.0 as usize].clone();
+    fn update_attribute(&mut self, updated_attribute: &UpdatedAttribute) {
         let mut attributes = self.attributes.borrow_mut();
-        attributes.insert(prop_name, updated_attribute.attribute.clone());
+        attributes.insert(
+            updated_attribute.object_id,
+            updated_attribute.attribute.clone(),
+        );
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub struct TimeSeriesReplayData {
-    pub time: f32,
-    pub delta: f32,
+time_series_columns! {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct TimeSeriesReplayData => TimeSeriesReplayColumns {
+        pub time: f32,
+        pub delta: f32,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct CleanedData {
-    pub players_actor: HashMap<WrappedUniqueId, HashMap<String, Attribute>>,
-    pub players_time_series_boost_data:
-        HashMap<WrappedUniqueId, HashMap<usize, TimeSeriesBoostData>>,
-    pub players_time_series_boost_pickup_data:
-        HashMap<WrappedUniqueId, HashMap<usize, Option<BoostPickupKind>>>,
+    pub players_actor: HashMap<WrappedUniqueId, ActorAttributes>,
+    /// Car data with gaps filled (if enabled), alongside per-player gap statistics.
+    /// Use `GapFilled::data` with the original data to get the (possibly filled) columns.
+    pub players_time_series_car_data: HashMap<WrappedUniqueId, GapFilled<TimeSeriesCarColumns>>,
+    pub time_series_ball_data: GapFilled<TimeSeriesBallColumns>,
+    pub players_time_series_boost_data: HashMap<WrappedUniqueId, TimeSeriesBoostColumns>,
+    /// Boost pickups per frame, encoded with `BoostPickupKind::to_column_value`.
+    pub players_time_series_boost_pickup_data: HashMap<WrappedUniqueId, Vec<Option<u8>>>,
+    /// Pickups replicated by boost pad actors, with the pad picked up.
+    pub boost_pickups: Vec<BoostPickup>,
+    /// Differences between `boost_pickups` and `players_time_series_boost_pickup_data`.
+    pub boost_pickup_disagreements: Vec<BoostPickupDisagreement>,
+    /// Each pad's availability per frame, indexed as `BOOST_PADS_COORDS`. Empty for arenas without the standard pad layout.
+    pub boost_pads_availability: Vec<Vec<bool>>,
+    /// Each frame's phase, derived from game event data and goal frames.
+    pub game_phases: Vec<GamePhase>,
+    /// Each player's team per frame (whether orange), from their PRI's team actor.
+    pub players_time_series_team: HashMap<WrappedUniqueId, Vec<Option<bool>>>,
+    /// Joins, leaves and team changes, ordered by frame.
+    pub roster_events: Vec<RosterEvent>,
 }
 
 impl CleanedData {
     pub fn new() -> Self {
         Self {
             players_actor: HashMap::new(),
+            players_time_series_car_data: HashMap::new(),
+            time_series_ball_data: GapFilled {
+                filled_data: None,
+                is_filled: vec![],
+                stats: Default::default(),
+            },
             players_time_series_boost_data: HashMap::new(),
             players_time_series_boost_pickup_data: HashMap::new(),
+            boost_pickups: vec![],
+            boost_pickup_disagreements: vec![],
+            boost_pads_availability: vec![],
+            game_phases: vec![],
+            players_time_series_team: HashMap::new(),
+            roster_events: vec![],
         }
     }
 }
@@ -362,10 +828,610 @@ impl Default for CleanedData {
 
 #[derive(Debug, Clone, Error)]
 pub enum FrameParserError {
+    #[error("missing ReplayVersion from replay header properties")]
+    MissingReplayVersion,
     #[error("missing network frames from parsed replay")]
     MissingNetworkFrames,
     #[error("trying to update missing actor {1} on frame {0}")]
     ActorUpdateMissingIdError(usize, ActorId),
+    #[error("actor handler failed for actor {1} on frame {0}: {2}")]
+    ActorHandlerError(usize, ActorId, ActorHandlerError),
     #[error("clean up failed: {0}")]
     CleanUpError(BoostPickupKindCalculationError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor_handlers::ObjectNameMatcher;
+    use crate::cleaner::RosterEventKind;
+    use crate::outputs::{DataFramesOutput, Player, PlayerRole};
+    use boxcars::attributes::{ActiveActor, CamSettings, PickupNew, RemoteId, RigidBody, UniqueId};
+    use boxcars::{
+        Frame, NetworkFrames, ObjectId, Quaternion, StreamId, Trajectory, Vector3f, Vector3i,
+    };
+
+    pub(crate) fn replay(objects: Vec<String>, frames: Vec<Frame>) -> Replay {
+        Replay {
+            header_size: 0,
+            header_crc: 0,
+            major_version: 868,
+            minor_version: 29,
+            net_version: Some(10),
+            game_type: "TAGame.Replay_Soccar_TA".to_string(),
+            properties: vec![("ReplayVersion".to_string(), HeaderProp::Int(10))],
+            content_size: 0,
+            content_crc: 0,
+            network_frames: Some(NetworkFrames { frames }),
+            levels: vec![],
+            keyframes: vec![],
+            debug_info: vec![],
+            tick_marks: vec![],
+            packages: vec![],
+            objects,
+            names: vec![],
+            class_indices: vec![],
+            net_cache: vec![],
+        }
+    }
+
+    pub(crate) fn frame(
+        time: f32,
+        new_actors: Vec<NewActor>,
+        deleted_actors: Vec<ActorId>,
+        updated_actors: Vec<UpdatedAttribute>,
+    ) -> Frame {
+        Frame {
+            time,
+            delta: 0.1,
+            new_actors,
+            deleted_actors,
+            updated_actors,
+        }
+    }
+
+    pub(crate) fn new_actor(actor_id: i32, object_id: i32) -> NewActor {
+        NewActor {
+            actor_id: ActorId(actor_id),
+            name_id: None,
+            object_id: ObjectId(object_id),
+            initial_trajectory: Trajectory {
+                location: None,
+                rotation: None,
+            },
+        }
+    }
+
+    pub(crate) fn updated_attribute(
+        actor_id: i32,
+        object_id: i32,
+        attribute: Attribute,
+    ) -> UpdatedAttribute {
+        UpdatedAttribute {
+            actor_id: ActorId(actor_id),
+            stream_id: StreamId(0),
+            object_id: ObjectId(object_id),
+            attribute,
+        }
+    }
+
+    /// A replay with a ball that spawns on the first frame and moves on the second.
+    fn ball_replay() -> Replay {
+        let objects = vec![
+            "Archetypes.Ball.Ball_Default".to_string(),
+            AttributeKey::RigidBodyState.object_name().to_string(),
+        ];
+        let ball_actor_id = ActorId(1);
+        let frames = vec![
+            Frame {
+                time: 1.0,
+                delta: 0.0,
+                new_actors: vec![NewActor {
+                    actor_id: ball_actor_id,
+                    name_id: None,
+                    object_id: ObjectId(0),
+                    initial_trajectory: Trajectory {
+                        location: Some(Vector3i { x: 0, y: 0, z: 93 }),
+                        rotation: None,
+                    },
+                }],
+                deleted_actors: vec![],
+                updated_actors: vec![],
+            },
+            Frame {
+                time: 1.5,
+                delta: 0.5,
+                new_actors: vec![],
+                deleted_actors: vec![],
+                updated_actors: vec![UpdatedAttribute {
+                    actor_id: ball_actor_id,
+                    stream_id: StreamId(0),
+                    object_id: ObjectId(1),
+                    attribute: Attribute::RigidBody(RigidBody {
+                        sleeping: false,
+                        location: Vector3f {
+                            x: 100.0,
+                            y: 0.0,
+                            z: 93.0,
+                        },
+                        rotation: Quaternion {
+                            x: 0.0,
+                            y: 0.0,
+                            z: 0.0,
+                            w: 1.0,
+                        },
+                        linear_velocity: Some(Vector3f {
+                            x: 200.0,
+                            y: 0.0,
+                            z: 0.0,
+                        }),
+                        angular_velocity: Some(Vector3f {
+                            x: 0.0,
+                            y: 0.0,
+                            z: 0.0,
+                        }),
+                    }),
+                }],
+            },
+        ];
+        replay(objects, frames)
+    }
+
+    #[test]
+    fn frames_yield_snapshots_without_storing() {
+        let replay = ball_replay();
+        let frame_parser = FrameParser::new(&replay).unwrap();
+        let frame_snapshots: Vec<FrameSnapshot> = frame_parser
+            .frames(&replay, false)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        assert_eq!(frame_snapshots.len(), 2);
+        assert_eq!(frame_snapshots[1].frame_number, 1);
+        assert_eq!(
+            frame_snapshots[1].replay_data,
+            TimeSeriesReplayData {
+                time: 1.5,
+                delta: 0.5
+            }
+        );
+        let ball_data: Vec<(Option<f32>, Option<f32>)> = frame_snapshots
+            .iter()
+            .map(|frame_snapshot| {
+                let ball_data = frame_snapshot.ball_data.unwrap();
+                (ball_data.pos_x, ball_data.vel_x)
+            })
+            .collect();
+        assert_eq!(
+            ball_data,
+            vec![(Some(0.0), None), (Some(100.0), Some(200.0))]
+        );
+        assert!(frame_parser.time_series_ball_data.borrow().is_empty());
+    }
+
+    #[test]
+    fn process_replay_stores_the_same_snapshots() {
+        let replay = ball_replay();
+        let frame_parser = FrameParser::new(&replay).unwrap();
+        let frame_snapshots: Vec<FrameSnapshot> = frame_parser
+            .frames(&replay, false)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+
+        let parsed_replay = ParsedReplay::from_replay(&replay, false).unwrap();
+        for frame_snapshot in frame_snapshots.iter() {
+            assert_eq!(
+                parsed_replay
+                    .time_series_ball_data
+                    .get(frame_snapshot.frame_number),
+                frame_snapshot.ball_data
+            );
+            assert_eq!(
+                parsed_replay
+                    .time_series_replay_data
+                    .get(frame_snapshot.frame_number),
+                Some(frame_snapshot.replay_data)
+            );
+        }
+    }
+
+    /// A replay with two players on the blue team, one of whom leaves on the second of three frames, and a spectator.
+    fn leaver_replay() -> Replay {
+        let objects: Vec<String> = [
+            "Archetypes.Teams.Team0",
+            "TAGame.Default__PRI_TA",
+            AttributeKey::UniqueId.object_name(),
+            AttributeKey::PlayerName.object_name(),
+            AttributeKey::PlayerTeam.object_name(),
+        ]
+        .iter()
+        .map(|object| object.to_string())
+        .collect();
+        let player_attributes = |actor_id: i32, online_id: u64, name: &str| {
+            vec![
+                updated_attribute(
+                    actor_id,
+                    2,
+                    Attribute::UniqueId(Box::new(UniqueId {
+                        system_id: 1,
+                        remote_id: RemoteId::Steam(online_id),
+                        local_id: 0,
+                    })),
+                ),
+                updated_attribute(actor_id, 3, Attribute::String(name.to_string())),
+                updated_attribute(
+                    actor_id,
+                    4,
+                    Attribute::ActiveActor(ActiveActor {
+                        active: true,
+                        actor: ActorId(1),
+                    }),
+                ),
+            ]
+        };
+        let mut updated_actors = player_attributes(2, 1, "Stayer");
+        updated_actors.extend(player_attributes(3, 2, "Leaver"));
+        // A spectator, never on a team.
+        updated_actors.extend(player_attributes(4, 3, "Spectator").into_iter().take(2));
+        let frames = vec![
+            frame(
+                0.0,
+                vec![
+                    new_actor(1, 0),
+                    new_actor(2, 1),
+                    new_actor(3, 1),
+                    new_actor(4, 1),
+                ],
+                vec![],
+                updated_actors,
+            ),
+            frame(0.1, vec![], vec![ActorId(3)], vec![]),
+            frame(0.2, vec![], vec![], vec![]),
+        ];
+        replay(objects, frames)
+    }
+
+    #[test]
+    fn leavers_and_spectators_are_kept_in_players() {
+        let parsed_replay = ParsedReplay::from_replay(&leaver_replay(), false).unwrap();
+        let leaver_id = WrappedUniqueId::Online(UniqueId {
+            system_id: 1,
+            remote_id: RemoteId::Steam(2),
+            local_id: 0,
+        });
+        assert_eq!(parsed_replay.players_actor.len(), 3);
+        assert!(parsed_replay.players_actor.contains_key(&leaver_id));
+
+        let mut players_role: Vec<(String, PlayerRole)> =
+            Player::from_parsed_replay(&parsed_replay)
+                .into_iter()
+                .map(|player| (player.name, player.role))
+                .collect();
+        players_role.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            players_role,
+            vec![
+                ("Leaver".to_string(), PlayerRole::Player),
+                ("Spectator".to_string(), PlayerRole::Spectator),
+                ("Stayer".to_string(), PlayerRole::Player),
+            ]
+        );
+
+        let roster_events: Vec<(usize, RosterEventKind, Option<bool>)> = parsed_replay
+            .cleaned_data
+            .roster_events
+            .iter()
+            .filter(|roster_event| roster_event.unique_id == leaver_id)
+            .map(|roster_event| {
+                (
+                    roster_event.frame_number,
+                    roster_event.kind,
+                    roster_event.is_orange,
+                )
+            })
+            .collect();
+        assert_eq!(
+            roster_events,
+            vec![
+                (0, RosterEventKind::Joined, Some(false)),
+                (1, RosterEventKind::Left, Some(false)),
+            ]
+        );
+        assert_eq!(
+            parsed_replay.cleaned_data.players_time_series_team[&leaver_id],
+            vec![Some(false), None, None]
+        );
+    }
+
+    /// A car at rest on the ground at the center of the field.
+    fn resting_car_rigid_body() -> Attribute {
+        Attribute::RigidBody(RigidBody {
+            sleeping: false,
+            location: Vector3f {
+                x: 0.0,
+                y: 0.0,
+                z: 17.0,
+            },
+            rotation: Quaternion {
+                x: 0.0,
+                y: 0.0,
+                z: 0.0,
+                w: 1.0,
+            },
+            linear_velocity: None,
+            angular_velocity: None,
+        })
+    }
+
+    /// A replay with a player's car and a boost pad, whose pickup state is replicated on each of three frames.
+    fn boost_pickup_replay() -> Replay {
+        let objects: Vec<String> = [
+            "TAGame.Default__PRI_TA",
+            "Archetypes.Car.Car_Default",
+            "Stadium_P.TheWorld:PersistentLevel.VehiclePickup_Boost_TA_1",
+            AttributeKey::UniqueId.object_name(),
+            AttributeKey::PawnPlayerReplicationInfo.object_name(),
+            AttributeKey::RigidBodyState.object_name(),
+            AttributeKey::NewReplicatedPickupData.object_name(),
+        ]
+        .iter()
+        .map(|object| object.to_string())
+        .collect();
+        let pickup = |picked_up: u8| {
+            updated_attribute(
+                3,
+                6,
+                Attribute::PickupNew(PickupNew {
+                    instigator: Some(ActorId(2)),
+                    picked_up,
+                }),
+            )
+        };
+        let mut car_actor = new_actor(2, 1);
+        car_actor.initial_trajectory.location = Some(Vector3i { x: 0, y: 0, z: 17 });
+        let frames = vec![
+            frame(
+                0.0,
+                vec![new_actor(1, 0), car_actor, new_actor(3, 2)],
+                vec![],
+                vec![
+                    updated_attribute(
+                        1,
+                        3,
+                        Attribute::UniqueId(Box::new(UniqueId {
+                            system_id: 1,
+                            remote_id: RemoteId::Steam(1),
+                            local_id: 0,
+                        })),
+                    ),
+                    updated_attribute(
+                        2,
+                        4,
+                        Attribute::ActiveActor(ActiveActor {
+                            active: true,
+                            actor: ActorId(1),
+                        }),
+                    ),
+                    updated_attribute(2, 5, resting_car_rigid_body()),
+                    pickup(3),
+                ],
+            ),
+            frame(0.1, vec![], vec![], vec![pickup(3)]),
+            frame(0.2, vec![], vec![], vec![pickup(4)]),
+        ];
+        replay(objects, frames)
+    }
+
+    #[test]
+    fn first_replicated_pickup_state_is_not_a_pickup() {
+        let replay = boost_pickup_replay();
+        let frame_parser = FrameParser::new(&replay).unwrap();
+        for frame_snapshot in frame_parser.frames(&replay, false).unwrap() {
+            frame_snapshot.unwrap();
+        }
+        let pickup_frames: Vec<usize> = frame_parser
+            .boost_pickups_data
+            .borrow()
+            .iter()
+            .map(|boost_pickup_data| boost_pickup_data.frame_number)
+            .collect();
+        assert_eq!(pickup_frames, vec![2]);
+    }
+
+    /// Records the ball's replicated x position on each frame.
+    struct BallPositionHandler<'a> {
+        frame_parser: &'a FrameParser,
+    }
+
+    impl<'a> ActorHandler<'a> for BallPositionHandler<'a> {
+        fn new(frame_parser: &'a FrameParser) -> Self {
+            Self { frame_parser }
+        }
+
+        fn update(
+            &mut self,
+            actor: &Actor,
+            frame_number: usize,
+            _time: f32,
+            _delta: f32,
+        ) -> Result<(), ActorHandlerError> {
+            let pos_x = match actor.attributes.borrow().get(AttributeKey::RigidBodyState) {
+                Some(Attribute::RigidBody(rigid_body)) => Some(rigid_body.location.x),
+                _ => None,
+            };
+            self.frame_parser
+                .with_custom_output("ball_pos_x", |output: &mut Vec<(usize, Option<f32>)>| {
+                    output.push((frame_number, pos_x))
+                });
+            Ok(())
+        }
+    }
+
+    fn create_ball_position_handler<'a>(
+        frame_parser: &'a FrameParser,
+    ) -> Box<dyn ActorHandler<'a> + 'a> {
+        Box::new(BallPositionHandler::new(frame_parser))
+    }
+
+    #[test]
+    fn custom_handlers_run_alongside_built_in_handlers() {
+        let custom_actor_handler = |name: &str, matcher| {
+            CustomActorHandler::new(
+                name,
+                matcher,
+                create_ball_position_handler,
+                Vec::<(usize, Option<f32>)>::new(),
+            )
+        };
+        let options = ParseOptions {
+            custom_actor_handlers: vec![
+                custom_actor_handler(
+                    "ball_pos_x",
+                    ObjectNameMatcher::StartsWith("Archetypes.Ball.".to_string()),
+                ),
+                custom_actor_handler(
+                    "unmatched",
+                    ObjectNameMatcher::Exact("Archetypes.Ball".to_string()),
+                ),
+            ],
+            ..ParseOptions::default()
+        };
+        let parsed_replay =
+            ParsedReplay::from_replay_with_options(&ball_replay(), &options).unwrap();
+
+        assert_eq!(
+            parsed_replay.custom_output::<Vec<(usize, Option<f32>)>>("ball_pos_x"),
+            Some(&vec![(0, None), (1, Some(100.0))])
+        );
+        assert_eq!(
+            parsed_replay.custom_output::<Vec<(usize, Option<f32>)>>("unmatched"),
+            Some(&vec![])
+        );
+        // The built-in ball handler still ran.
+        assert_eq!(
+            parsed_replay
+                .time_series_ball_data
+                .get(1)
+                .and_then(|ball_data| ball_data.pos_x),
+            Some(100.0)
+        );
+    }
+
+    /// A replay with a player (with a car and boost) whose camera settings actor toggles ball cam off on the second of three frames.
+    fn camera_settings_replay() -> Replay {
+        let objects: Vec<String> = [
+            "TAGame.Default__PRI_TA",
+            "TAGame.Default__CameraSettingsActor_TA",
+            AttributeKey::UniqueId.object_name(),
+            AttributeKey::PlayerName.object_name(),
+            AttributeKey::CameraSettingsPlayer.object_name(),
+            AttributeKey::CameraProfileSettings.object_name(),
+            AttributeKey::UsingSecondaryCamera.object_name(),
+            "Archetypes.Car.Car_Default",
+            "Archetypes.CarComponents.CarComponent_Boost",
+            AttributeKey::PawnPlayerReplicationInfo.object_name(),
+            AttributeKey::RigidBodyState.object_name(),
+            AttributeKey::CarComponentVehicle.object_name(),
+        ]
+        .iter()
+        .map(|object| object.to_string())
+        .collect();
+        let mut car_actor = new_actor(3, 7);
+        car_actor.initial_trajectory.location = Some(Vector3i { x: 0, y: 0, z: 17 });
+        let frames = vec![
+            frame(
+                0.0,
+                vec![new_actor(1, 0), new_actor(2, 1), car_actor, new_actor(4, 8)],
+                vec![],
+                vec![
+                    updated_attribute(
+                        1,
+                        2,
+                        Attribute::UniqueId(Box::new(UniqueId {
+                            system_id: 1,
+                            remote_id: RemoteId::Steam(1),
+                            local_id: 0,
+                        })),
+                    ),
+                    updated_attribute(1, 3, Attribute::String("Player".to_string())),
+                    updated_attribute(
+                        2,
+                        4,
+                        Attribute::ActiveActor(ActiveActor {
+                            active: true,
+                            actor: ActorId(1),
+                        }),
+                    ),
+                    updated_attribute(
+                        2,
+                        5,
+                        Attribute::CamSettings(Box::new(CamSettings {
+                            fov: 110.0,
+                            height: 100.0,
+                            angle: -4.0,
+                            distance: 270.0,
+                            stiffness: 0.5,
+                            swivel: 5.0,
+                            transition: Some(1.2),
+                        })),
+                    ),
+                    updated_attribute(2, 6, Attribute::Boolean(true)),
+                    updated_attribute(
+                        3,
+                        9,
+                        Attribute::ActiveActor(ActiveActor {
+                            active: true,
+                            actor: ActorId(1),
+                        }),
+                    ),
+                    updated_attribute(3, 10, resting_car_rigid_body()),
+                    updated_attribute(
+                        4,
+                        11,
+                        Attribute::ActiveActor(ActiveActor {
+                            active: true,
+                            actor: ActorId(3),
+                        }),
+                    ),
+                ],
+            ),
+            frame(
+                0.1,
+                vec![],
+                vec![],
+                vec![updated_attribute(2, 6, Attribute::Boolean(false))],
+            ),
+            frame(0.2, vec![], vec![], vec![]),
+        ];
+        replay(objects, frames)
+    }
+
+    #[test]
+    fn camera_settings_are_linked_to_their_player() {
+        let parsed_replay = ParsedReplay::from_replay(&camera_settings_replay(), false).unwrap();
+        let unique_id = WrappedUniqueId::Online(UniqueId {
+            system_id: 1,
+            remote_id: RemoteId::Steam(1),
+            local_id: 0,
+        });
+        let camera_settings = parsed_replay.players_camera_settings[&unique_id];
+        assert_eq!(camera_settings.fov, 110.0);
+        assert_eq!(camera_settings.transition, Some(1.2));
+        assert_eq!(
+            parsed_replay.players_time_series_camera_data[&unique_id].ball_cam,
+            vec![Some(true), Some(false), Some(false)]
+        );
+
+        let data_frames = DataFramesOutput::generate_from(&parsed_replay).unwrap();
+        let ball_cam: Vec<Option<u8>> = data_frames.players[&unique_id]
+            .column("ball_cam")
+            .unwrap()
+            .u8()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(ball_cam, vec![Some(1), Some(0), Some(0)]);
+    }
+}
@@ -1,15 +1,23 @@
+#[macro_use]
+mod time_series;
+
 pub mod actor_handlers;
 pub mod analysis;
+pub mod batch;
 pub mod cleaner;
 pub mod frame_parser;
 pub mod outputs;
 
-use crate::frame_parser::{FrameParser, FrameParserError};
+use crate::actor_handlers::CustomActorHandler;
+use crate::cleaner::GapFillOptions;
+use crate::frame_parser::{FrameParserError, ParsedReplay};
+use crate::outputs::{HeaderOutput, OutputError};
 use boxcars::HeaderProp;
 use boxcars::{CrcCheck, NetworkParse, ParseError, ParserBuilder, Replay};
 use std::collections::HashMap;
 use std::fs;
 use std::io;
+use std::io::Read;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -17,10 +25,14 @@ use thiserror::Error;
 pub enum CarballError {
     #[error("failed to read file: {0}")]
     ReadFileError(io::Error),
+    #[error("failed to read replay data: {0}")]
+    ReadError(io::Error),
     #[error("boxcars failed to parse: {0}")]
     BoxCarsParseError(ParseError),
     #[error("failed to parse frames: {0}")]
     FrameParserError(FrameParserError),
+    #[error("failed to generate outputs: {0}")]
+    OutputError(OutputError),
 }
 
 impl From<ParseError> for CarballError {
@@ -33,36 +45,129 @@ impl From<FrameParserError> for CarballError {
         CarballError::FrameParserError(err)
     }
 }
+impl From<OutputError> for CarballError {
+    fn from(err: OutputError) -> CarballError {
+        CarballError::OutputError(err)
+    }
+}
+
+/// Options controlling how replay data is parsed.
+#[derive(Debug, Clone)]
+pub struct ParseOptions {
+    /// When boxcars should verify the replay's CRC.
+    pub crc_check: CrcCheck,
+    /// Whether boxcars should parse the network data (required for frame parsing).
+    pub network_parse: NetworkParse,
+    pub show_progress: bool,
+    /// How gaps in car and ball data are filled after parsing, if at all.
+    pub gap_fill: Option<GapFillOptions>,
+    /// Custom handlers run on matching actors, in addition to the built-in handlers.
+    /// Their outputs can be retrieved with `ParsedReplay::custom_output`.
+    pub custom_actor_handlers: Vec<CustomActorHandler>,
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            crc_check: CrcCheck::Always,
+            network_parse: NetworkParse::Always,
+            show_progress: false,
+            gap_fill: None,
+            custom_actor_handlers: vec![],
+        }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct CarballParser {
-    pub file_path: PathBuf,
+    pub file_path: Option<PathBuf>,
     pub replay: Replay,
-    pub frame_parser: FrameParser,
+    pub parsed_replay: ParsedReplay,
 }
 
 impl CarballParser {
     pub fn parse_file(file_path: PathBuf, show_progress: bool) -> Result<Self, CarballError> {
-        let replay = read_file(&file_path)?;
+        Self::parse_file_with_options(
+            file_path,
+            ParseOptions {
+                show_progress,
+                ..ParseOptions::default()
+            },
+        )
+    }
+
+    pub fn parse_file_with_options(
+        file_path: PathBuf,
+        options: ParseOptions,
+    ) -> Result<Self, CarballError> {
+        let replay = read_file_with_options(&file_path, &options)?;
+        let mut carball_parser = Self::from_replay(replay, &options)?;
+        carball_parser.file_path = Some(file_path);
+        Ok(carball_parser)
+    }
+
+    pub fn parse_bytes(data: &[u8], options: ParseOptions) -> Result<Self, CarballError> {
+        let replay = read_bytes(data, &options)?;
+        Self::from_replay(replay, &options)
+    }
 
-        let frame_parser = FrameParser::from_replay(&replay, show_progress)?;
+    pub fn parse_reader<R: Read>(
+        mut reader: R,
+        options: ParseOptions,
+    ) -> Result<Self, CarballError> {
+        let mut data = vec![];
+        reader
+            .read_to_end(&mut data)
+            .map_err(CarballError::ReadError)?;
+        Self::parse_bytes(&data, options)
+    }
+
+    fn from_replay(replay: Replay, options: &ParseOptions) -> Result<Self, CarballError> {
+        let parsed_replay = ParsedReplay::from_replay_with_options(&replay, options)?;
 
         Ok(Self {
-            file_path,
+            file_path: None,
             replay,
-            frame_parser,
+            parsed_replay,
         })
     }
 }
 
 pub fn read_file(file_path: &Path) -> Result<Replay, CarballError> {
+    read_file_with_options(file_path, &ParseOptions::default())
+}
+
+pub fn read_file_with_options(
+    file_path: &Path,
+    options: &ParseOptions,
+) -> Result<Replay, CarballError> {
     let data = fs::read(file_path).map_err(CarballError::ReadFileError)?;
-    Ok(ParserBuilder::new(&data[..])
-        .with_crc_check(CrcCheck::Always)
-        .with_network_parse(NetworkParse::Always)
+    read_bytes(&data, options)
+}
+
+pub fn read_bytes(data: &[u8], options: &ParseOptions) -> Result<Replay, CarballError> {
+    Ok(ParserBuilder::new(data)
+        .with_crc_check(options.crc_check)
+        .with_network_parse(options.network_parse)
         .parse()?)
 }
 
+/// Parses only the replay header, skipping network data entirely.
+/// This is much faster than a full parse when only match metadata is required.
+pub fn parse_header_file(file_path: &Path) -> Result<HeaderOutput, CarballError> {
+    let data = fs::read(file_path).map_err(CarballError::ReadFileError)?;
+    parse_header_bytes(&data)
+}
+
+pub fn parse_header_bytes(data: &[u8]) -> Result<HeaderOutput, CarballError> {
+    let options = ParseOptions {
+        network_parse: NetworkParse::Never,
+        ..ParseOptions::default()
+    };
+    let replay = read_bytes(data, &options)?;
+    Ok(HeaderOutput::generate_from(&replay)?)
+}
+
 fn replay_properties_to_hash_map(replay: &Replay) -> HashMap<&str, &HeaderProp> {
     // Convert from Vec to HashMap. boxcars uses a Vec to allow for potential duplicate keys.
     replay
@@ -71,3 +176,14 @@ fn replay_properties_to_hash_map(replay: &Replay) -> HashMap<&str, &HeaderProp>
         .map(|x| (&x.0 as &str, &x.1))
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn carball_parser_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<CarballParser>();
+    }
+}
@@ -1,30 +1,45 @@
 #[macro_use]
 extern crate log;
 
-use carball::analysis::CarballAnalyzer;
-use carball::outputs::RangeChecker;
-use carball::outputs::{
-    DataFrameOutputFormat, DataFramesOutput, MetadataOutput, ParseOutputWriter,
-};
-use carball::CarballParser;
+use carball::batch::{find_replay_files, process_batch, process_replay_file, ProcessOptions};
+use carball::cleaner::{GapFillMethod, GapFillOptions};
+use carball::outputs::DataFrameOutputFormat;
+use carball::ParseOptions;
 use simplelog::*;
 use std::path::PathBuf;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
 struct Opt {
+    /// Replay file, or a directory or glob pattern of replay files with --batch.
     #[structopt(short, parse(from_os_str))]
     input: PathBuf,
     #[structopt(short, parse(from_os_str))]
     output_dir: PathBuf,
+    /// Parse all replays matched by the input in parallel, writing each replay's outputs into its own subdirectory.
+    #[structopt(long)]
+    batch: bool,
+    /// Number of threads used in batch mode (defaults to the number of cores).
+    #[structopt(long)]
+    threads: Option<usize>,
+    /// Only parse the replay header, writing header.json without parsing network data.
+    #[structopt(long)]
+    header_only: bool,
     #[structopt(long)]
     skip_data_frames: bool,
     #[structopt(long)]
     skip_write_data_frames: bool,
 
-    #[structopt(required_unless_one(&["skip_data_frames", "skip_write_data_frames"]), possible_values = &DataFrameOutputFormat::variants(), case_insensitive = true)]
+    #[structopt(required_unless_one(&["header_only", "skip_data_frames", "skip_write_data_frames"]), possible_values = &DataFrameOutputFormat::variants(), case_insensitive = true)]
     data_frame_output_format: Option<DataFrameOutputFormat>,
 
+    /// Fill gaps in car and ball data of up to this many frames, extrapolating from the last known velocity.
+    #[structopt(long)]
+    fill_gaps: Option<usize>,
+    /// Resample written DataFrames to a fixed rate in Hz (e.g. 30, 60, 120), interpolating between frames.
+    #[structopt(long)]
+    resample_rate: Option<f32>,
+
     #[structopt(long)]
     skip_checks: bool,
 
@@ -39,46 +54,40 @@ fn main() {
     // dbg!(&opt);
     info!("{:?}", &opt);
 
-    let carball_parser = CarballParser::parse_file(opt.input, true).expect("Failed to parse.");
-
-    let metadata =
-        MetadataOutput::generate_from(&carball_parser.replay, &carball_parser.frame_parser);
-    let data_frames = if opt.skip_data_frames {
-        None
-    } else {
-        Some(
-            DataFramesOutput::generate_from(&carball_parser.frame_parser)
-                .expect("Failed to generate data frames."),
-        )
+    let options = ProcessOptions {
+        parse_options: ParseOptions {
+            show_progress: true,
+            gap_fill: opt.fill_gaps.map(|max_gap_frames| GapFillOptions {
+                method: GapFillMethod::Extrapolate,
+                max_gap_frames,
+            }),
+            ..ParseOptions::default()
+        },
+        data_frame_output_format: opt.data_frame_output_format,
+        header_only: opt.header_only,
+        skip_data_frames: opt.skip_data_frames,
+        skip_write_data_frames: opt.skip_write_data_frames,
+        resample_rate: opt.resample_rate,
+        skip_checks: opt.skip_checks,
+        skip_analysis: opt.skip_analysis,
     };
 
-    if !opt.skip_data_frames && !opt.skip_checks {
-        if let Some(_data_frames) = &data_frames {
-            let range_checker = RangeChecker::new();
-            range_checker
-                .check_ranges(_data_frames)
-                .expect("Failed to complete range checks.");
+    if opt.batch {
+        if let Some(threads) = opt.threads {
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build_global()
+                .expect("Failed to set up thread pool.");
         }
-    }
-
-    let parse_output_writer =
-        ParseOutputWriter::new(opt.output_dir.clone(), opt.data_frame_output_format);
-    if opt.skip_write_data_frames {
-        parse_output_writer
-            .write_outputs(Some(&metadata), None)
-            .expect("Failed to write outputs.");
+        let replay_paths =
+            find_replay_files(&opt.input.to_string_lossy()).expect("Failed to find replays.");
+        info!("Found {} replays", replay_paths.len());
+        let summary = process_batch(&replay_paths, &opt.output_dir, &options)
+            .expect("Failed to process batch.");
+        info!("{} succeeded, {} failed", summary.succeeded, summary.failed);
     } else {
-        parse_output_writer
-            .write_outputs(Some(&metadata), data_frames.as_ref())
-            .expect("Failed to write outputs.");
-    }
-
-    if !opt.skip_data_frames && !opt.skip_analysis {
-        let analyzer = CarballAnalyzer::analyze(&carball_parser, &metadata, &data_frames.unwrap())
-            .expect("Failed to analyze.");
-        analyzer
-            .write(opt.output_dir)
-            .expect("Failed to write analysis.");
+        process_replay_file(&opt.input, &opt.output_dir, &options)
+            .expect("Failed to process replay.");
     }
 
     info!("fin");
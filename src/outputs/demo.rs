@@ -1,5 +1,5 @@
 use crate::actor_handlers::{DemoData, WrappedUniqueId};
-use crate::frame_parser::FrameParser;
+use crate::frame_parser::ParsedReplay;
 use serde::Serialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
@@ -10,9 +10,8 @@ pub struct Demo {
 }
 
 impl Demo {
-    pub fn from_frame_parser(frame_parser: &FrameParser) -> Vec<Self> {
-        let demos_data = frame_parser.demos_data.borrow();
-        demos_data.iter().map(Demo::from).collect()
+    pub fn from_parsed_replay(parsed_replay: &ParsedReplay) -> Vec<Self> {
+        parsed_replay.demos_data.iter().map(Demo::from).collect()
     }
 
     pub fn from(demo_data: &DemoData) -> Self {
@@ -1,10 +1,14 @@
+use crate::cleaner::{BallType, GameMode};
+use crate::outputs::HeaderPlayerStats;
 use crate::replay_properties_to_hash_map;
 use boxcars::{HeaderProp, Replay};
 use log::error;
 use serde::Serialize;
 use std::collections::HashMap;
+use thiserror::Error;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// Not `Eq`, as `record_fps`, `keyframe_delay` and `total_seconds_played` are floats.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Game {
     pub id: String,
     pub replay_version: i32,
@@ -13,79 +17,162 @@ pub struct Game {
     pub map_name: Option<String>,
     pub date: Option<String>,
     pub match_type: Option<String>,
+    /// Only in newer builds.
+    pub match_guid: Option<String>,
+    pub build_version: Option<String>,
+    pub build_id: Option<i32>,
+    pub changelist: Option<i32>,
+    pub record_fps: Option<f32>,
+    pub keyframe_delay: Option<f32>,
+    pub max_channels: Option<i32>,
+    /// Name of the player who recorded the replay.
+    pub recorder_name: Option<String>,
+    pub primary_player_is_orange: Option<bool>,
+    pub team_size: Option<i32>,
+    pub unfair_team_size: Option<i32>,
+    pub is_forfeit: bool,
+    pub total_seconds_played: Option<f32>,
+    pub game_mode: GameMode,
+    pub ball_type: BallType,
     pub team_0_score: Option<i32>,
     pub team_1_score: Option<i32>,
     pub goals: Vec<Goal>,
+    pub highlights: Vec<Highlight>,
+    /// Only includes players present at the end of the match.
+    pub player_stats: Vec<HeaderPlayerStats>,
 }
 
 impl Game {
-    pub fn from(replay: &Replay) -> Self {
+    /// `game_mode` is the mode detected from the network frames (`ParsedReplay::game_mode`), if parsed.
+    /// Otherwise it is detected from the header alone.
+    pub fn from(replay: &Replay, game_mode: Option<GameMode>) -> Result<Self, HeaderError> {
         let properties = replay_properties_to_hash_map(replay);
 
-        match properties.get("Id") {
-            Some(HeaderProp::Str(id)) => match properties.get("ReplayVersion") {
-                Some(HeaderProp::Int(replay_version)) => match properties.get("NumFrames") {
-                    Some(HeaderProp::Int(num_frames)) => {
-                        let mut replay_name = None;
-                        let mut map_name = None;
-                        let mut date = None;
-                        let mut match_type = None;
-                        let mut team_0_score = None;
-                        let mut team_1_score = None;
+        let id = match properties.get("Id") {
+            Some(HeaderProp::Str(id)) => id.to_string(),
+            Some(_) => return Err(HeaderError::UnexpectedPropertyType("Id")),
+            None => return Err(HeaderError::MissingProperty("Id")),
+        };
+        let replay_version = match properties.get("ReplayVersion") {
+            Some(HeaderProp::Int(replay_version)) => *replay_version,
+            Some(_) => return Err(HeaderError::UnexpectedPropertyType("ReplayVersion")),
+            None => return Err(HeaderError::MissingProperty("ReplayVersion")),
+        };
+        let num_frames = match properties.get("NumFrames") {
+            Some(HeaderProp::Int(num_frames)) => *num_frames,
+            Some(_) => return Err(HeaderError::UnexpectedPropertyType("NumFrames")),
+            None => return Err(HeaderError::MissingProperty("NumFrames")),
+        };
 
-                        if let Some(HeaderProp::Str(_replay_name)) = properties.get("ReplayName") {
-                            replay_name = Some(_replay_name.to_string());
-                        }
-                        if let Some(HeaderProp::Name(_map_name)) = properties.get("MapName") {
-                            map_name = Some(_map_name.to_string());
-                        }
-                        if let Some(HeaderProp::Str(_date)) = properties.get("Date") {
-                            date = Some(_date.to_string());
-                        }
-                        if let Some(HeaderProp::Name(_match_type)) = properties.get("MatchType") {
-                            match_type = Some(_match_type.to_string());
-                        }
-                        if let Some(HeaderProp::Int(_team_0_score)) = properties.get("Team0Score") {
-                            team_0_score = Some(*_team_0_score);
-                        }
-                        if let Some(HeaderProp::Int(_team_1_score)) = properties.get("Team1Score") {
-                            team_1_score = Some(*_team_1_score);
-                        }
+        let mut replay_name = None;
+        let mut map_name = None;
+        let mut date = None;
+        let mut match_type = None;
+        let mut team_0_score = None;
+        let mut team_1_score = None;
+        let mut match_guid = None;
+        let mut build_version = None;
+        let mut build_id = None;
+        let mut changelist = None;
+        let mut record_fps = None;
+        let mut keyframe_delay = None;
+        let mut max_channels = None;
+        let mut recorder_name = None;
+        let mut primary_player_is_orange = None;
+        let mut team_size = None;
+        let mut unfair_team_size = None;
+        let mut total_seconds_played = None;
 
-                        Self {
-                            id: id.to_string(),
-                            replay_version: *replay_version,
-                            num_frames: *num_frames,
-                            replay_name,
-                            map_name,
-                            date,
-                            match_type,
-                            team_0_score,
-                            team_1_score,
-                            goals: Goal::from_replay_properties(&properties),
-                        }
-                    }
-                    Some(_) => {
-                        panic!("replay header's NumFrames property has unexpected type");
-                    }
-                    None => {
-                        panic!("replay header has no NumFrames property");
-                    }
-                },
-                Some(_) => {
-                    panic!("replay header's ReplayVersion property has unexpected type");
-                }
-                None => {
-                    panic!("replay header has no ReplayVersion property");
-                }
-            },
-            Some(_) => {
-                panic!("replay header's Id property has unexpected type");
-            }
-            None => {
-                panic!("replay header has no Id property");
-            }
+        if let Some(HeaderProp::Str(_replay_name)) = properties.get("ReplayName") {
+            replay_name = Some(_replay_name.to_string());
+        }
+        if let Some(HeaderProp::Name(_map_name)) = properties.get("MapName") {
+            map_name = Some(_map_name.to_string());
+        }
+        if let Some(HeaderProp::Str(_date)) = properties.get("Date") {
+            date = Some(_date.to_string());
+        }
+        if let Some(HeaderProp::Name(_match_type)) = properties.get("MatchType") {
+            match_type = Some(_match_type.to_string());
+        }
+        if let Some(HeaderProp::Int(_team_0_score)) = properties.get("Team0Score") {
+            team_0_score = Some(*_team_0_score);
+        }
+        if let Some(HeaderProp::Int(_team_1_score)) = properties.get("Team1Score") {
+            team_1_score = Some(*_team_1_score);
+        }
+        if let Some(HeaderProp::Str(_match_guid)) = properties.get("MatchGuid") {
+            match_guid = Some(_match_guid.to_string());
         }
+        if let Some(HeaderProp::Str(_build_version)) = properties.get("BuildVersion") {
+            build_version = Some(_build_version.to_string());
+        }
+        if let Some(HeaderProp::Int(_build_id)) = properties.get("BuildID") {
+            build_id = Some(*_build_id);
+        }
+        if let Some(HeaderProp::Int(_changelist)) = properties.get("Changelist") {
+            changelist = Some(*_changelist);
+        }
+        if let Some(HeaderProp::Float(_record_fps)) = properties.get("RecordFPS") {
+            record_fps = Some(*_record_fps);
+        }
+        if let Some(HeaderProp::Float(_keyframe_delay)) = properties.get("KeyframeDelay") {
+            keyframe_delay = Some(*_keyframe_delay);
+        }
+        if let Some(HeaderProp::Int(_max_channels)) = properties.get("MaxChannels") {
+            max_channels = Some(*_max_channels);
+        }
+        if let Some(HeaderProp::Str(_recorder_name)) = properties.get("PlayerName") {
+            recorder_name = Some(_recorder_name.to_string());
+        }
+        if let Some(HeaderProp::Int(_primary_player_team)) = properties.get("PrimaryPlayerTeam") {
+            primary_player_is_orange = Some(*_primary_player_team == 1);
+        }
+        if let Some(HeaderProp::Int(_team_size)) = properties.get("TeamSize") {
+            team_size = Some(*_team_size);
+        }
+        if let Some(HeaderProp::Int(_unfair_team_size)) = properties.get("UnfairTeamSize") {
+            unfair_team_size = Some(*_unfair_team_size);
+        }
+        let is_forfeit = matches!(properties.get("bForfeit"), Some(HeaderProp::Bool(true)));
+        if let Some(HeaderProp::Float(_total_seconds_played)) = properties.get("TotalSecondsPlayed")
+        {
+            total_seconds_played = Some(*_total_seconds_played);
+        }
+
+        let game_mode =
+            game_mode.unwrap_or_else(|| GameMode::from_replay(replay, map_name.as_deref()));
+        let ball_type = BallType::from_replay(replay, game_mode);
+
+        Ok(Self {
+            id,
+            replay_version,
+            num_frames,
+            replay_name,
+            map_name,
+            date,
+            match_type,
+            match_guid,
+            build_version,
+            build_id,
+            changelist,
+            record_fps,
+            keyframe_delay,
+            max_channels,
+            recorder_name,
+            primary_player_is_orange,
+            team_size,
+            unfair_team_size,
+            is_forfeit,
+            total_seconds_played,
+            game_mode,
+            ball_type,
+            team_0_score,
+            team_1_score,
+            goals: Goal::from_replay_properties(&properties),
+            highlights: Highlight::from_replay_properties(&properties),
+            player_stats: HeaderPlayerStats::from_replay_properties(&properties),
+        })
     }
 }
 
@@ -108,7 +195,7 @@ impl Goal {
     }
 
     pub fn from(data: &[(String, HeaderProp)]) -> Self {
-        let goal_properties: HashMap<String, HeaderProp> = data.to_owned().into_iter().collect();
+        let goal_properties: HashMap<String, HeaderProp> = data.iter().cloned().collect();
         Goal {
             frame: match goal_properties.get("frame") {
                 Some(HeaderProp::Int(frame)) => *frame,
@@ -134,3 +221,53 @@ impl Goal {
         }
     }
 }
+
+/// A goal or save saved to the replay's highlights.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Highlight {
+    pub frame: i32,
+    pub car_name: Option<String>,
+    pub ball_name: Option<String>,
+}
+
+impl Highlight {
+    pub fn from_replay_properties(properties: &HashMap<&str, &HeaderProp>) -> Vec<Self> {
+        match properties.get("HighLights") {
+            Some(HeaderProp::Array(highlights)) => {
+                highlights.iter().map(|h| Highlight::from(h)).collect()
+            }
+            // Not all replays have highlights.
+            _ => vec![],
+        }
+    }
+
+    pub fn from(data: &[(String, HeaderProp)]) -> Self {
+        let highlight_properties: HashMap<&str, &HeaderProp> =
+            data.iter().map(|x| (&x.0 as &str, &x.1)).collect();
+        Highlight {
+            frame: match highlight_properties.get("frame") {
+                Some(HeaderProp::Int(frame)) => *frame,
+                _ => {
+                    error!("Could not find frame for highlight.");
+                    0
+                }
+            },
+            car_name: match highlight_properties.get("CarName") {
+                Some(HeaderProp::Name(car_name)) => Some(car_name.to_string()),
+                _ => None,
+            },
+            ball_name: match highlight_properties.get("BallName") {
+                Some(HeaderProp::Name(ball_name)) => Some(ball_name.to_string()),
+                _ => None,
+            },
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HeaderError {
+    #[error("replay header has no {0} property")]
+    MissingProperty(&'static str),
+    #[error("replay header's {0} property has unexpected type")]
+    UnexpectedPropertyType(&'static str),
+}
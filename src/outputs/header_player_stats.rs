@@ -0,0 +1,94 @@
+use crate::actor_handlers::WrappedUniqueId;
+use crate::outputs::Player;
+use boxcars::HeaderProp;
+use log::error;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Player stats from the replay header's PlayerStats property.
+/// These are available without parsing network frames, but only include players present at the end of the match.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HeaderPlayerStats {
+    pub name: String,
+    pub platform: Option<String>,
+    pub online_id: Option<String>,
+    pub is_orange: Option<bool>,
+    pub score: i32,
+    pub goals: i32,
+    pub assists: i32,
+    pub saves: i32,
+    pub shots: i32,
+    pub is_bot: bool,
+    /// The matching `Player`, found by online id (or by name for players without one).
+    /// Only set when network frames are parsed.
+    pub unique_id: Option<WrappedUniqueId>,
+}
+
+impl HeaderPlayerStats {
+    pub fn from_replay_properties(properties: &HashMap<&str, &HeaderProp>) -> Vec<Self> {
+        match properties.get("PlayerStats") {
+            Some(HeaderProp::Array(players)) => {
+                players.iter().map(|p| HeaderPlayerStats::from(p)).collect()
+            }
+            _ => {
+                error!("Failed to parse PlayerStats key in replay properties.");
+                vec![]
+            }
+        }
+    }
+
+    pub fn from(data: &[(String, HeaderProp)]) -> Self {
+        let player_properties: HashMap<&str, &HeaderProp> =
+            data.iter().map(|x| (&x.0 as &str, &x.1)).collect();
+        let get_int = |key: &str| match player_properties.get(key) {
+            Some(HeaderProp::Int(value)) => *value,
+            _ => 0,
+        };
+        HeaderPlayerStats {
+            name: match player_properties.get("Name") {
+                Some(HeaderProp::Str(name)) => name.to_string(),
+                _ => {
+                    error!("Could not find name for header player stats.");
+                    "".to_string()
+                }
+            },
+            platform: match player_properties.get("Platform") {
+                Some(HeaderProp::Byte {
+                    value: Some(platform),
+                    ..
+                }) => Some(platform.to_string()),
+                _ => None,
+            },
+            online_id: match player_properties.get("OnlineID") {
+                Some(HeaderProp::QWord(online_id)) => Some(online_id.to_string()),
+                _ => None,
+            },
+            is_orange: match player_properties.get("Team") {
+                Some(HeaderProp::Int(team)) => Some(*team == 1),
+                _ => None,
+            },
+            score: get_int("Score"),
+            goals: get_int("Goals"),
+            assists: get_int("Assists"),
+            saves: get_int("Saves"),
+            shots: get_int("Shots"),
+            is_bot: matches!(player_properties.get("bBot"), Some(HeaderProp::Bool(true))),
+            unique_id: None,
+        }
+    }
+
+    pub fn link_players(header_player_stats: &mut [Self], players: &[Player]) {
+        for player_stats in header_player_stats.iter_mut() {
+            let has_online_id = !matches!(player_stats.online_id.as_deref(), None | Some("0"));
+            player_stats.unique_id = players
+                .iter()
+                .find(|player| has_online_id && player.online_id == player_stats.online_id)
+                .or_else(|| {
+                    players
+                        .iter()
+                        .find(|player| !has_online_id && player.name == player_stats.name)
+                })
+                .map(|player| player.unique_id.clone());
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use crate::actor_handlers::{ActorAttributes, AttributeKey};
+use boxcars::attributes::{Loadout, ProductValue};
+use boxcars::Attribute;
+use serde::Serialize;
+
+static PAINTED_PRODUCT_ATTRIBUTE: &str = "TAGame.ProductAttribute_Painted_TA";
+
+/// Product ids of the player's car items, for the team the player was on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CarLoadout {
+    pub body: u32,
+    pub decal: u32,
+    pub wheels: u32,
+    pub boost: u32,
+    pub antenna: u32,
+    pub topper: u32,
+    /// Paint id of the car body.
+    pub paint: Option<u32>,
+}
+
+impl CarLoadout {
+    /// Reads the loadout from the PRI's `ClientLoadouts` (or the older `ClientLoadout`), and the paint from `ClientLoadoutsOnline`.
+    pub fn from(attributes: &ActorAttributes, is_orange: bool) -> Option<Self> {
+        let loadout: &Loadout = match attributes.get(AttributeKey::ClientLoadouts) {
+            Some(Attribute::TeamLoadout(team_loadout)) => {
+                if is_orange {
+                    &team_loadout.orange
+                } else {
+                    &team_loadout.blue
+                }
+            }
+            _ => match attributes.get(AttributeKey::ClientLoadout) {
+                Some(Attribute::Loadout(loadout)) => loadout,
+                _ => return None,
+            },
+        };
+        Some(Self::from_loadout(
+            loadout,
+            get_body_paint(attributes, is_orange),
+        ))
+    }
+
+    fn from_loadout(loadout: &Loadout, paint: Option<u32>) -> Self {
+        Self {
+            body: loadout.body,
+            decal: loadout.decal,
+            wheels: loadout.wheels,
+            boost: loadout.rocket_trail,
+            antenna: loadout.antenna,
+            topper: loadout.topper,
+            paint,
+        }
+    }
+}
+
+fn get_body_paint(attributes: &ActorAttributes, is_orange: bool) -> Option<u32> {
+    let painted_object_id = attributes
+        .object_ids()
+        .get_by_name(PAINTED_PRODUCT_ATTRIBUTE)?;
+    let loadouts_online = match attributes.get(AttributeKey::ClientLoadoutsOnline) {
+        Some(Attribute::LoadoutsOnline(loadouts_online)) => loadouts_online,
+        _ => return None,
+    };
+    let products = if is_orange {
+        &loadouts_online.orange
+    } else {
+        &loadouts_online.blue
+    };
+    // Items are in loadout order, starting with the body.
+    products
+        .first()?
+        .iter()
+        .filter(|product| product.object_ind == painted_object_id.0 as u32)
+        .find_map(|product| match product.value {
+            ProductValue::OldPaint(paint) | ProductValue::NewPaint(paint) => Some(paint),
+            _ => None,
+        })
+}
+
+/// Car bodies share one of a few hitboxes.
+/// Dimensions are those published on the RLBot wiki's "Useful Game Values" page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub enum HitboxFamily {
+    Octane,
+    Dominus,
+    Plank,
+    Breakout,
+    Hybrid,
+    Merc,
+}
+
+/// Hitbox size and the offset of its centre from the car's position.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct HitboxDimensions {
+    pub length: f32,
+    pub width: f32,
+    pub height: f32,
+    pub offset_x: f32,
+    pub offset_z: f32,
+}
+
+impl HitboxFamily {
+    /// Looks up the hitbox of a car body product id. Returns None for bodies not in the table.
+    /// The table only covers early and common bodies, and is missing many (e.g. the Ripper, 600).
+    pub fn from_body(body: u32) -> Option<Self> {
+        match body {
+            21 | 23 | 25 | 26 | 402 | 404 | 523 | 607 | 625 | 723 | 1172 | 1295 | 1300 | 1568
+            | 2665 | 2853 | 2919 | 3031 => Some(HitboxFamily::Octane),
+            29 | 403 | 597 | 1018 | 1171 | 1286 | 1603 | 1623 | 2268 | 2666 => {
+                Some(HitboxFamily::Dominus)
+            }
+            24 | 803 | 1691 | 1919 => Some(HitboxFamily::Plank),
+            22 | 1416 | 1932 | 2298 => Some(HitboxFamily::Breakout),
+            28 | 31 | 1159 | 1317 | 1624 | 1856 => Some(HitboxFamily::Hybrid),
+            30 => Some(HitboxFamily::Merc),
+            _ => None,
+        }
+    }
+
+    pub fn dimensions(self) -> HitboxDimensions {
+        let (length, width, height, offset_x, offset_z) = match self {
+            HitboxFamily::Octane => (118.01, 84.20, 36.16, 13.88, 20.75),
+            HitboxFamily::Dominus => (127.93, 83.28, 31.30, 9.00, 15.75),
+            HitboxFamily::Plank => (128.82, 84.67, 29.39, 9.01, 12.09),
+            HitboxFamily::Breakout => (131.49, 80.52, 30.30, 12.50, 11.75),
+            HitboxFamily::Hybrid => (127.02, 82.19, 34.16, 13.88, 20.75),
+            HitboxFamily::Merc => (120.72, 76.71, 41.66, 11.38, 21.50),
+        };
+        HitboxDimensions {
+            length,
+            width,
+            height,
+            offset_x,
+            offset_z,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hitbox_from_body() {
+        assert_eq!(HitboxFamily::from_body(23), Some(HitboxFamily::Octane));
+        assert_eq!(HitboxFamily::from_body(403), Some(HitboxFamily::Dominus));
+        assert_eq!(HitboxFamily::from_body(803), Some(HitboxFamily::Plank));
+        assert_eq!(HitboxFamily::from_body(22), Some(HitboxFamily::Breakout));
+        assert_eq!(HitboxFamily::from_body(28), Some(HitboxFamily::Hybrid));
+        assert_eq!(HitboxFamily::from_body(30), Some(HitboxFamily::Merc));
+        assert_eq!(HitboxFamily::from_body(0), None);
+    }
+
+    #[test]
+    fn hitbox_dimensions() {
+        let octane = HitboxFamily::Octane.dimensions();
+        assert_eq!(octane.length, 118.01);
+        let plank = HitboxFamily::Plank.dimensions();
+        assert!(plank.length > octane.length && plank.height < octane.height);
+    }
+}
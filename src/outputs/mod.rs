@@ -1,15 +1,24 @@
 pub mod demo;
 pub mod game;
+pub mod header_player_stats;
+pub mod loadout;
 pub mod output;
+pub mod party;
 pub mod player;
+pub mod power_up;
 pub mod range_check;
+pub mod resample;
 pub mod team;
 pub mod write;
 
 pub use self::demo::*;
 pub use self::game::*;
+pub use self::header_player_stats::*;
+pub use self::loadout::*;
 pub use self::output::*;
+pub use self::party::*;
 pub use self::player::*;
+pub use self::power_up::*;
 pub use self::range_check::*;
 pub use self::team::*;
 pub use self::write::*;
@@ -1,37 +1,82 @@
 use crate::actor_handlers::{
-    TimeSeriesBallData, TimeSeriesBoostData, TimeSeriesCarData, TimeSeriesGameEventData,
-    TimeSeriesPlayerData, WrappedUniqueId,
+    AttributeKey, TimeSeriesBallColumns, TimeSeriesBoostColumns, TimeSeriesCameraColumns,
+    TimeSeriesCarColumns, TimeSeriesCarComponentColumns, TimeSeriesGameEventColumns,
+    TimeSeriesPlayerColumns, TimeSeriesPowerUpColumns, WrappedUniqueId,
+};
+use crate::cleaner::{
+    BoostPickup, BoostPickupDisagreement, GamePhase, RosterEvent, BOOST_PADS_COORDS,
+};
+use crate::frame_parser::{ParsedReplay, TimeSeriesReplayColumns};
+use crate::outputs::{
+    Demo, Game, HeaderError, HeaderPlayerStats, Party, Player, PowerUpEvent, Team,
 };
-use crate::cleaner::BoostPickupKind;
-use crate::frame_parser::{FrameParser, TimeSeriesReplayData};
-use crate::outputs::{Demo, Game, Player, Team};
 use boxcars::{Attribute, Replay};
 use log::error;
 use polars::error::PolarsError;
 use polars::prelude::{
-    DataFrame, Float32Chunked, Int32Chunked, IntoSeries, NewChunkedArray, UInt8Chunked,
+    DataFrame, Float32Chunked, Int32Chunked, IntoSeries, NewChunkedArray, Series, UInt8Chunked,
 };
 use serde::Serialize;
 use std::collections::HashMap;
 use std::fmt::Debug;
 use thiserror::Error;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct MetadataOutput {
     pub game: Game,
     pub teams: Vec<Team>,
     pub players: Vec<Player>,
+    /// Players who queued together, keyed by the party leader's unique id.
+    pub parties: Vec<Party>,
     pub demos: Vec<Demo>,
+    pub power_ups: Vec<PowerUpEvent>,
+    pub boost_pickups: Vec<BoostPickup>,
+    pub boost_pickup_disagreements: Vec<BoostPickupDisagreement>,
+    pub roster_events: Vec<RosterEvent>,
 }
 
 impl MetadataOutput {
-    pub fn generate_from(replay: &Replay, frame_parser: &FrameParser) -> Self {
-        Self {
-            game: Game::from(replay),
-            teams: Team::from_frame_parser(frame_parser),
-            players: Player::from_frame_parser(frame_parser),
-            demos: Demo::from_frame_parser(frame_parser),
-        }
+    pub fn generate_from(
+        replay: &Replay,
+        parsed_replay: &ParsedReplay,
+    ) -> Result<Self, OutputError> {
+        let players = Player::from_parsed_replay(parsed_replay);
+        let mut game =
+            Game::from(replay, Some(parsed_replay.game_mode)).map_err(OutputError::HeaderError)?;
+        HeaderPlayerStats::link_players(&mut game.player_stats, &players);
+        Ok(Self {
+            game,
+            teams: Team::from_parsed_replay(parsed_replay),
+            parties: Party::from_players(&players),
+            players,
+            demos: Demo::from_parsed_replay(parsed_replay),
+            power_ups: PowerUpEvent::from_parsed_replay(parsed_replay),
+            boost_pickups: parsed_replay.cleaned_data.boost_pickups.clone(),
+            boost_pickup_disagreements: parsed_replay
+                .cleaned_data
+                .boost_pickup_disagreements
+                .clone(),
+            roster_events: parsed_replay.cleaned_data.roster_events.clone(),
+        })
+    }
+}
+
+/// Metadata available from the replay header alone, without parsing network frames.
+/// Not `Eq`, as `Game` has float fields.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HeaderOutput {
+    pub game: Game,
+    /// Same as `game.player_stats`.
+    pub players: Vec<HeaderPlayerStats>,
+}
+
+impl HeaderOutput {
+    pub fn generate_from(replay: &Replay) -> Result<Self, OutputError> {
+        let game = Game::from(replay, None).map_err(OutputError::HeaderError)?;
+        Ok(Self {
+            players: game.player_stats.clone(),
+            game,
+        })
     }
 }
 
@@ -40,19 +85,17 @@ pub struct DataFramesOutput {
     pub game: DataFrame,
     pub ball: DataFrame,
     pub players: HashMap<WrappedUniqueId, DataFrame>,
+    /// Availability of each boost pad, with a column per pad in `BOOST_PADS_COORDS`.
+    pub boost_pads: DataFrame,
 }
 
 impl DataFramesOutput {
-    pub fn generate_from(frame_parser: &FrameParser) -> Result<Self, OutputError> {
-        let frame_count = frame_parser.frame_count;
-        let players_actor = frame_parser.players_actor.borrow();
-        let players_time_series_car_data = frame_parser.players_time_series_car_data.borrow();
-        let players_time_series_player_data = frame_parser.players_time_series_player_data.borrow();
+    pub fn generate_from(parsed_replay: &ParsedReplay) -> Result<Self, OutputError> {
+        let players_actor = &parsed_replay.players_actor;
+        let players_time_series_player_data = &parsed_replay.players_time_series_player_data;
 
-        let cleaned_data = frame_parser
-            .cleaned_data
-            .as_ref()
-            .ok_or(OutputError::MissingCleanedData)?;
+        let cleaned_data = &parsed_replay.cleaned_data;
+        let players_time_series_car_data = &cleaned_data.players_time_series_car_data;
         let players_time_series_boost_data = &cleaned_data.players_time_series_boost_data;
         let players_time_series_boost_pickup_data =
             &cleaned_data.players_time_series_boost_pickup_data;
@@ -60,9 +103,7 @@ impl DataFramesOutput {
         // Create player dfs
         let mut player_dfs = HashMap::new();
         for (wrapped_unique_id, player_actor) in players_actor.iter() {
-            if let Attribute::String(player_name) = player_actor
-                .get("Engine.PlayerReplicationInfo:PlayerName")
-                .expect("Could not find player name")
+            if let Some(Attribute::String(player_name)) = player_actor.get(AttributeKey::PlayerName)
             {
                 if let Some(time_series_car_data) =
                     players_time_series_car_data.get(wrapped_unique_id)
@@ -77,11 +118,25 @@ impl DataFramesOutput {
                                 players_time_series_boost_pickup_data.get(wrapped_unique_id)
                             {
                                 let player_df = create_player_df(
-                                    time_series_car_data,
+                                    time_series_car_data.data(
+                                        &parsed_replay.players_time_series_car_data
+                                            [wrapped_unique_id],
+                                    ),
+                                    &time_series_car_data.is_filled,
                                     time_series_player_data,
+                                    cleaned_data.players_time_series_team.get(wrapped_unique_id),
                                     time_series_boost_data,
                                     time_series_boost_pickup_data,
-                                    frame_count,
+                                    parsed_replay
+                                        .players_time_series_camera_data
+                                        .get(wrapped_unique_id),
+                                    parsed_replay
+                                        .players_time_series_car_component_data
+                                        .get(wrapped_unique_id),
+                                    parsed_replay
+                                        .players_time_series_power_up_data
+                                        .get(wrapped_unique_id),
+                                    &cleaned_data.game_phases,
                                 )?;
                                 player_dfs.insert(wrapped_unique_id.clone(), player_df);
                             } else {
@@ -113,239 +168,265 @@ impl DataFramesOutput {
         }
 
         // Create ball df
-        let ball_df = create_ball_df(&frame_parser.time_series_ball_data.borrow(), frame_count)?;
+        let ball_df = create_ball_df(
+            cleaned_data
+                .time_series_ball_data
+                .data(&parsed_replay.time_series_ball_data),
+            &cleaned_data.time_series_ball_data.is_filled,
+            &cleaned_data.game_phases,
+        )?;
 
         // Create game df
         let game_df = create_game_df(
-            &frame_parser.time_series_replay_data.borrow(),
-            &frame_parser.time_series_game_event_data.borrow(),
-            frame_count,
+            &parsed_replay.time_series_replay_data,
+            &parsed_replay.time_series_game_event_data,
+            &cleaned_data.game_phases,
+        )?;
+
+        // Create boost pads df
+        let boost_pads_df = create_boost_pads_df(
+            &cleaned_data.boost_pads_availability,
+            parsed_replay.frame_count,
         )?;
 
         Ok(Self {
             game: game_df,
             ball: ball_df,
             players: player_dfs,
+            boost_pads: boost_pads_df,
         })
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn create_player_df(
-    time_series_car_data: &HashMap<usize, TimeSeriesCarData>,
-    time_series_player_data: &HashMap<usize, TimeSeriesPlayerData>,
-    time_series_boost_data: &HashMap<usize, TimeSeriesBoostData>,
-    time_series_boost_pickup_data: &HashMap<usize, Option<BoostPickupKind>>,
-    frame_count: usize,
+    time_series_car_data: &TimeSeriesCarColumns,
+    is_gap_filled: &[bool],
+    time_series_player_data: &TimeSeriesPlayerColumns,
+    player_teams: Option<&Vec<Option<bool>>>,
+    time_series_boost_data: &TimeSeriesBoostColumns,
+    time_series_boost_pickup_data: &[Option<u8>],
+    time_series_camera_data: Option<&TimeSeriesCameraColumns>,
+    time_series_car_component_data: Option<&TimeSeriesCarComponentColumns>,
+    time_series_power_up_data: Option<&TimeSeriesPowerUpColumns>,
+    game_phases: &[GamePhase],
 ) -> Result<DataFrame, OutputError> {
-    // Car data
-    let mut is_sleeping: Vec<Option<u8>> = vec![None; frame_count];
-    let mut pos_x: Vec<Option<f32>> = vec![None; frame_count];
-    let mut pos_y: Vec<Option<f32>> = vec![None; frame_count];
-    let mut pos_z: Vec<Option<f32>> = vec![None; frame_count];
-    let mut vel_x: Vec<Option<f32>> = vec![None; frame_count];
-    let mut vel_y: Vec<Option<f32>> = vec![None; frame_count];
-    let mut vel_z: Vec<Option<f32>> = vec![None; frame_count];
-    let mut quat_w: Vec<Option<f32>> = vec![None; frame_count];
-    let mut quat_x: Vec<Option<f32>> = vec![None; frame_count];
-    let mut quat_y: Vec<Option<f32>> = vec![None; frame_count];
-    let mut quat_z: Vec<Option<f32>> = vec![None; frame_count];
-    let mut ang_vel_x: Vec<Option<f32>> = vec![None; frame_count];
-    let mut ang_vel_y: Vec<Option<f32>> = vec![None; frame_count];
-    let mut ang_vel_z: Vec<Option<f32>> = vec![None; frame_count];
-    let mut throttle: Vec<Option<u8>> = vec![None; frame_count];
-    let mut steer: Vec<Option<u8>> = vec![None; frame_count];
-    let mut handbrake: Vec<Option<u8>> = vec![None; frame_count];
-
-    // Player data
-    let mut match_score: Vec<Option<i32>> = vec![None; frame_count];
-    let mut match_goals: Vec<Option<i32>> = vec![None; frame_count];
-    let mut match_assists: Vec<Option<i32>> = vec![None; frame_count];
-    let mut match_saves: Vec<Option<i32>> = vec![None; frame_count];
-    let mut match_shots: Vec<Option<i32>> = vec![None; frame_count];
-    let mut team: Vec<Option<i32>> = vec![None; frame_count];
-    let mut ping: Vec<Option<u8>> = vec![None; frame_count];
-
-    // Boost data
-    let mut boost_is_active: Vec<Option<u8>> = vec![None; frame_count];
-    let mut boost_amount: Vec<Option<f32>> = vec![None; frame_count];
-
-    // Boost pickup data
-    let mut boost_pickup: Vec<Option<u8>> = vec![None; frame_count];
-
-    for (frame_number, data) in time_series_car_data.iter() {
-        is_sleeping[*frame_number] = data.is_sleeping.map(u8::from);
-        pos_x[*frame_number] = data.pos_x;
-        pos_y[*frame_number] = data.pos_y;
-        pos_z[*frame_number] = data.pos_z;
-        vel_x[*frame_number] = data.vel_x;
-        vel_y[*frame_number] = data.vel_y;
-        vel_z[*frame_number] = data.vel_z;
-        quat_w[*frame_number] = data.quat_w;
-        quat_x[*frame_number] = data.quat_x;
-        quat_y[*frame_number] = data.quat_y;
-        quat_z[*frame_number] = data.quat_z;
-        ang_vel_x[*frame_number] = data.ang_vel_x;
-        ang_vel_y[*frame_number] = data.ang_vel_y;
-        ang_vel_z[*frame_number] = data.ang_vel_z;
-        throttle[*frame_number] = data.throttle;
-        steer[*frame_number] = data.steer;
-        handbrake[*frame_number] = data.handbrake;
-    }
-    for (frame_number, data) in time_series_player_data.iter() {
-        match_score[*frame_number] = data.match_score;
-        match_goals[*frame_number] = data.match_goals;
-        match_assists[*frame_number] = data.match_assists;
-        match_saves[*frame_number] = data.match_saves;
-        match_shots[*frame_number] = data.match_shots;
-        team[*frame_number] = data.team;
-        ping[*frame_number] = data.ping;
-    }
-    for (frame_number, data) in time_series_boost_data.iter() {
-        boost_is_active[*frame_number] = data.boost_is_active.map(u8::from);
-        boost_amount[*frame_number] = data.boost_amount;
-    }
-    for (frame_number, _boost_pickup) in time_series_boost_pickup_data.iter() {
-        match _boost_pickup {
-            Some(BoostPickupKind::Full) => boost_pickup[*frame_number] = Some(2),
-            Some(BoostPickupKind::Small) => boost_pickup[*frame_number] = Some(1),
-            None => boost_pickup[*frame_number] = Some(0),
-        };
-    }
-
-    DataFrame::new(vec![
-        UInt8Chunked::new_from_opt_slice("is_sleeping", &is_sleeping).into_series(),
-        Float32Chunked::new_from_opt_slice("pos_x", &pos_x).into_series(),
-        Float32Chunked::new_from_opt_slice("pos_y", &pos_y).into_series(),
-        Float32Chunked::new_from_opt_slice("pos_z", &pos_z).into_series(),
-        Float32Chunked::new_from_opt_slice("vel_x", &vel_x).into_series(),
-        Float32Chunked::new_from_opt_slice("vel_y", &vel_y).into_series(),
-        Float32Chunked::new_from_opt_slice("vel_z", &vel_z).into_series(),
-        Float32Chunked::new_from_opt_slice("quat_w", &quat_w).into_series(),
-        Float32Chunked::new_from_opt_slice("quat_x", &quat_x).into_series(),
-        Float32Chunked::new_from_opt_slice("quat_y", &quat_y).into_series(),
-        Float32Chunked::new_from_opt_slice("quat_z", &quat_z).into_series(),
-        Float32Chunked::new_from_opt_slice("ang_vel_x", &ang_vel_x).into_series(),
-        Float32Chunked::new_from_opt_slice("ang_vel_y", &ang_vel_y).into_series(),
-        Float32Chunked::new_from_opt_slice("ang_vel_z", &ang_vel_z).into_series(),
-        UInt8Chunked::new_from_opt_slice("throttle", &throttle).into_series(),
-        UInt8Chunked::new_from_opt_slice("steer", &steer).into_series(),
-        UInt8Chunked::new_from_opt_slice("handbrake", &handbrake).into_series(),
-        Int32Chunked::new_from_opt_slice("match_score", &match_score).into_series(),
-        Int32Chunked::new_from_opt_slice("match_goals", &match_goals).into_series(),
-        Int32Chunked::new_from_opt_slice("match_assists", &match_assists).into_series(),
-        Int32Chunked::new_from_opt_slice("match_saves", &match_saves).into_series(),
-        Int32Chunked::new_from_opt_slice("match_shots", &match_shots).into_series(),
-        Int32Chunked::new_from_opt_slice("team", &team).into_series(),
-        UInt8Chunked::new_from_opt_slice("ping", &ping).into_series(),
-        UInt8Chunked::new_from_opt_slice("boost_is_active", &boost_is_active).into_series(),
-        Float32Chunked::new_from_opt_slice("boost_amount", &boost_amount).into_series(),
-        UInt8Chunked::new_from_opt_slice("boost_pickup", &boost_pickup).into_series(),
-    ])
-    .map_err(OutputError::CreateDataFrameError)
+    // Players without a camera settings actor (e.g. bots) have no ball cam data.
+    let ball_cam = match time_series_camera_data {
+        Some(_time_series_camera_data) => _time_series_camera_data.ball_cam.clone(),
+        None => vec![None; time_series_car_data.len()],
+    };
+    // Car components are only replicated once the player has driven a car.
+    let empty_car_component_data;
+    let time_series_car_component_data = match time_series_car_component_data {
+        Some(_time_series_car_component_data) => _time_series_car_component_data,
+        None => {
+            empty_car_component_data =
+                TimeSeriesCarComponentColumns::new(time_series_car_data.len());
+            &empty_car_component_data
+        }
+    };
+    // Only Rumble players hold power-ups, encoded with `PowerUp::to_column_value`.
+    let held_powerup = match time_series_power_up_data {
+        Some(_time_series_power_up_data) => _time_series_power_up_data.held_powerup.clone(),
+        None => vec![None; time_series_car_data.len()],
+    };
+    // Team is 0 (blue) or 1 (orange) as UInt8, and null where the player is not on a team (rather than guessed).
+    let team = match player_teams {
+        Some(_player_teams) => _player_teams.clone(),
+        None => vec![None; time_series_car_data.len()],
+    };
+    let mut columns = vec![
+        // Car data
+        bool_series("is_sleeping", &time_series_car_data.is_sleeping),
+        Float32Chunked::new_from_opt_slice("pos_x", &time_series_car_data.pos_x).into_series(),
+        Float32Chunked::new_from_opt_slice("pos_y", &time_series_car_data.pos_y).into_series(),
+        Float32Chunked::new_from_opt_slice("pos_z", &time_series_car_data.pos_z).into_series(),
+        Float32Chunked::new_from_opt_slice("vel_x", &time_series_car_data.vel_x).into_series(),
+        Float32Chunked::new_from_opt_slice("vel_y", &time_series_car_data.vel_y).into_series(),
+        Float32Chunked::new_from_opt_slice("vel_z", &time_series_car_data.vel_z).into_series(),
+        Float32Chunked::new_from_opt_slice("quat_w", &time_series_car_data.quat_w).into_series(),
+        Float32Chunked::new_from_opt_slice("quat_x", &time_series_car_data.quat_x).into_series(),
+        Float32Chunked::new_from_opt_slice("quat_y", &time_series_car_data.quat_y).into_series(),
+        Float32Chunked::new_from_opt_slice("quat_z", &time_series_car_data.quat_z).into_series(),
+        Float32Chunked::new_from_opt_slice("ang_vel_x", &time_series_car_data.ang_vel_x)
+            .into_series(),
+        Float32Chunked::new_from_opt_slice("ang_vel_y", &time_series_car_data.ang_vel_y)
+            .into_series(),
+        Float32Chunked::new_from_opt_slice("ang_vel_z", &time_series_car_data.ang_vel_z)
+            .into_series(),
+        UInt8Chunked::new_from_opt_slice("throttle", &time_series_car_data.throttle).into_series(),
+        UInt8Chunked::new_from_opt_slice("steer", &time_series_car_data.steer).into_series(),
+        UInt8Chunked::new_from_opt_slice("handbrake", &time_series_car_data.handbrake)
+            .into_series(),
+        gap_filled_series(is_gap_filled),
+        // Player data
+        Int32Chunked::new_from_opt_slice("match_score", &time_series_player_data.match_score)
+            .into_series(),
+        Int32Chunked::new_from_opt_slice("match_goals", &time_series_player_data.match_goals)
+            .into_series(),
+        Int32Chunked::new_from_opt_slice("match_assists", &time_series_player_data.match_assists)
+            .into_series(),
+        Int32Chunked::new_from_opt_slice("match_saves", &time_series_player_data.match_saves)
+            .into_series(),
+        Int32Chunked::new_from_opt_slice("match_shots", &time_series_player_data.match_shots)
+            .into_series(),
+        bool_series("team", &team),
+        UInt8Chunked::new_from_opt_slice("ping", &time_series_player_data.ping).into_series(),
+        // Boost data
+        bool_series("boost_is_active", &time_series_boost_data.boost_is_active),
+        Float32Chunked::new_from_opt_slice("boost_amount", &time_series_boost_data.boost_amount)
+            .into_series(),
+        // Boost pickup data
+        UInt8Chunked::new_from_opt_slice("boost_pickup", time_series_boost_pickup_data)
+            .into_series(),
+        // Car component data
+        bool_series("jump_active", &time_series_car_component_data.jump_active),
+        bool_series(
+            "double_jump_active",
+            &time_series_car_component_data.double_jump_active,
+        ),
+        bool_series("dodge_active", &time_series_car_component_data.dodge_active),
+        Float32Chunked::new_from_opt_slice(
+            "dodge_torque_x",
+            &time_series_car_component_data.dodge_torque_x,
+        )
+        .into_series(),
+        Float32Chunked::new_from_opt_slice(
+            "dodge_torque_y",
+            &time_series_car_component_data.dodge_torque_y,
+        )
+        .into_series(),
+        bool_series(
+            "flip_car_active",
+            &time_series_car_component_data.flip_car_active,
+        ),
+        // Power-up data
+        UInt8Chunked::new_from_opt_slice("held_powerup", &held_powerup).into_series(),
+        // Camera data
+        bool_series("ball_cam", &ball_cam),
+    ];
+    columns.extend(game_phase_series(game_phases));
+    DataFrame::new(columns).map_err(OutputError::CreateDataFrameError)
 }
 
 fn create_ball_df(
-    time_series_ball_data: &HashMap<usize, TimeSeriesBallData>,
-    frame_count: usize,
+    time_series_ball_data: &TimeSeriesBallColumns,
+    is_gap_filled: &[bool],
+    game_phases: &[GamePhase],
 ) -> Result<DataFrame, OutputError> {
-    let mut is_sleeping: Vec<Option<u8>> = vec![None; frame_count];
-    let mut pos_x: Vec<Option<f32>> = vec![None; frame_count];
-    let mut pos_y: Vec<Option<f32>> = vec![None; frame_count];
-    let mut pos_z: Vec<Option<f32>> = vec![None; frame_count];
-    let mut vel_x: Vec<Option<f32>> = vec![None; frame_count];
-    let mut vel_y: Vec<Option<f32>> = vec![None; frame_count];
-    let mut vel_z: Vec<Option<f32>> = vec![None; frame_count];
-    let mut quat_w: Vec<Option<f32>> = vec![None; frame_count];
-    let mut quat_x: Vec<Option<f32>> = vec![None; frame_count];
-    let mut quat_y: Vec<Option<f32>> = vec![None; frame_count];
-    let mut quat_z: Vec<Option<f32>> = vec![None; frame_count];
-    let mut ang_vel_x: Vec<Option<f32>> = vec![None; frame_count];
-    let mut ang_vel_y: Vec<Option<f32>> = vec![None; frame_count];
-    let mut ang_vel_z: Vec<Option<f32>> = vec![None; frame_count];
-    let mut hit_team_num: Vec<Option<u8>> = vec![None; frame_count];
-
-    for (frame_number, data) in time_series_ball_data.iter() {
-        is_sleeping[*frame_number] = data.is_sleeping.map(u8::from);
-        pos_x[*frame_number] = data.pos_x;
-        pos_y[*frame_number] = data.pos_y;
-        pos_z[*frame_number] = data.pos_z;
-        vel_x[*frame_number] = data.vel_x;
-        vel_y[*frame_number] = data.vel_y;
-        vel_z[*frame_number] = data.vel_z;
-        quat_w[*frame_number] = data.quat_w;
-        quat_x[*frame_number] = data.quat_x;
-        quat_y[*frame_number] = data.quat_y;
-        quat_z[*frame_number] = data.quat_z;
-        ang_vel_x[*frame_number] = data.ang_vel_x;
-        ang_vel_y[*frame_number] = data.ang_vel_y;
-        ang_vel_z[*frame_number] = data.ang_vel_z;
-        hit_team_num[*frame_number] = data.hit_team_num;
-    }
-
-    DataFrame::new(vec![
-        UInt8Chunked::new_from_opt_slice("is_sleeping", &is_sleeping).into_series(),
-        Float32Chunked::new_from_opt_slice("pos_x", &pos_x).into_series(),
-        Float32Chunked::new_from_opt_slice("pos_y", &pos_y).into_series(),
-        Float32Chunked::new_from_opt_slice("pos_z", &pos_z).into_series(),
-        Float32Chunked::new_from_opt_slice("vel_x", &vel_x).into_series(),
-        Float32Chunked::new_from_opt_slice("vel_y", &vel_y).into_series(),
-        Float32Chunked::new_from_opt_slice("vel_z", &vel_z).into_series(),
-        Float32Chunked::new_from_opt_slice("quat_w", &quat_w).into_series(),
-        Float32Chunked::new_from_opt_slice("quat_x", &quat_x).into_series(),
-        Float32Chunked::new_from_opt_slice("quat_y", &quat_y).into_series(),
-        Float32Chunked::new_from_opt_slice("quat_z", &quat_z).into_series(),
-        Float32Chunked::new_from_opt_slice("ang_vel_x", &ang_vel_x).into_series(),
-        Float32Chunked::new_from_opt_slice("ang_vel_y", &ang_vel_y).into_series(),
-        Float32Chunked::new_from_opt_slice("ang_vel_z", &ang_vel_z).into_series(),
-        UInt8Chunked::new_from_opt_slice("hit_team_num", &hit_team_num).into_series(),
-    ])
-    .map_err(OutputError::CreateDataFrameError)
+    let mut columns = vec![
+        bool_series("is_sleeping", &time_series_ball_data.is_sleeping),
+        Float32Chunked::new_from_opt_slice("pos_x", &time_series_ball_data.pos_x).into_series(),
+        Float32Chunked::new_from_opt_slice("pos_y", &time_series_ball_data.pos_y).into_series(),
+        Float32Chunked::new_from_opt_slice("pos_z", &time_series_ball_data.pos_z).into_series(),
+        Float32Chunked::new_from_opt_slice("vel_x", &time_series_ball_data.vel_x).into_series(),
+        Float32Chunked::new_from_opt_slice("vel_y", &time_series_ball_data.vel_y).into_series(),
+        Float32Chunked::new_from_opt_slice("vel_z", &time_series_ball_data.vel_z).into_series(),
+        Float32Chunked::new_from_opt_slice("quat_w", &time_series_ball_data.quat_w).into_series(),
+        Float32Chunked::new_from_opt_slice("quat_x", &time_series_ball_data.quat_x).into_series(),
+        Float32Chunked::new_from_opt_slice("quat_y", &time_series_ball_data.quat_y).into_series(),
+        Float32Chunked::new_from_opt_slice("quat_z", &time_series_ball_data.quat_z).into_series(),
+        Float32Chunked::new_from_opt_slice("ang_vel_x", &time_series_ball_data.ang_vel_x)
+            .into_series(),
+        Float32Chunked::new_from_opt_slice("ang_vel_y", &time_series_ball_data.ang_vel_y)
+            .into_series(),
+        Float32Chunked::new_from_opt_slice("ang_vel_z", &time_series_ball_data.ang_vel_z)
+            .into_series(),
+        UInt8Chunked::new_from_opt_slice("hit_team_num", &time_series_ball_data.hit_team_num)
+            .into_series(),
+        gap_filled_series(is_gap_filled),
+    ];
+    columns.extend(game_phase_series(game_phases));
+    DataFrame::new(columns).map_err(OutputError::CreateDataFrameError)
 }
 
 fn create_game_df(
-    time_series_replay_data: &HashMap<usize, TimeSeriesReplayData>,
-    time_series_game_event_data: &HashMap<usize, TimeSeriesGameEventData>,
-    frame_count: usize,
+    time_series_replay_data: &TimeSeriesReplayColumns,
+    time_series_game_event_data: &TimeSeriesGameEventColumns,
+    game_phases: &[GamePhase],
 ) -> Result<DataFrame, OutputError> {
-    let mut time: Vec<Option<f32>> = vec![None; frame_count];
-    let mut delta: Vec<Option<f32>> = vec![None; frame_count];
-
-    let mut seconds_remaining: Vec<Option<i32>> = vec![None; frame_count];
-    let mut replicated_game_state_time_remaining: Vec<Option<i32>> = vec![None; frame_count];
-    let mut is_overtime: Vec<Option<u8>> = vec![None; frame_count];
-    let mut ball_has_been_hit: Vec<Option<u8>> = vec![None; frame_count];
-
-    for (frame_number, data) in time_series_game_event_data.iter() {
-        seconds_remaining[*frame_number] = data.seconds_remaining;
-        replicated_game_state_time_remaining[*frame_number] =
-            data.replicated_game_state_time_remaining;
-        is_overtime[*frame_number] = data.is_overtime.map(u8::from);
-        ball_has_been_hit[*frame_number] = data.ball_has_been_hit.map(u8::from);
-    }
-    for (frame_number, data) in time_series_replay_data.iter() {
-        time[*frame_number] = Some(data.time);
-        delta[*frame_number] = Some(data.delta);
-    }
-
-    DataFrame::new(vec![
-        Float32Chunked::new_from_opt_slice("time", &time).into_series(),
-        Float32Chunked::new_from_opt_slice("delta", &delta).into_series(),
-        Int32Chunked::new_from_opt_slice("seconds_remaining", &seconds_remaining).into_series(),
+    let mut columns = vec![
+        Float32Chunked::new_from_slice("time", &time_series_replay_data.time).into_series(),
+        Float32Chunked::new_from_slice("delta", &time_series_replay_data.delta).into_series(),
+        Int32Chunked::new_from_opt_slice(
+            "seconds_remaining",
+            &time_series_game_event_data.seconds_remaining,
+        )
+        .into_series(),
         Int32Chunked::new_from_opt_slice(
             "replicated_game_state_time_remaining",
-            &replicated_game_state_time_remaining,
+            &time_series_game_event_data.replicated_game_state_time_remaining,
         )
         .into_series(),
-        UInt8Chunked::new_from_opt_slice("is_overtime", &is_overtime).into_series(),
-        UInt8Chunked::new_from_opt_slice("ball_has_been_hit", &ball_has_been_hit).into_series(),
-    ])
-    .map_err(OutputError::CreateDataFrameError)
+        bool_series("is_overtime", &time_series_game_event_data.is_overtime),
+        bool_series(
+            "ball_has_been_hit",
+            &time_series_game_event_data.ball_has_been_hit,
+        ),
+        bool_series("match_ended", &time_series_game_event_data.match_ended),
+    ];
+    columns.extend(game_phase_series(game_phases));
+    DataFrame::new(columns).map_err(OutputError::CreateDataFrameError)
+}
+
+/// Pad availability is null for arenas without the standard pad layout.
+fn create_boost_pads_df(
+    boost_pads_availability: &[Vec<bool>],
+    frame_count: usize,
+) -> Result<DataFrame, OutputError> {
+    let columns = (0..BOOST_PADS_COORDS.len())
+        .map(|pad_index| {
+            let name = format!("pad_{}_is_available", pad_index);
+            let is_available: Vec<Option<bool>> = match boost_pads_availability.get(pad_index) {
+                Some(pad_availability) => pad_availability.iter().copied().map(Some).collect(),
+                None => vec![None; frame_count],
+            };
+            bool_series(&name, &is_available)
+        })
+        .collect();
+    DataFrame::new(columns).map_err(OutputError::CreateDataFrameError)
+}
+
+/// Creates the `phase` (encoded with `GamePhase::to_column_value`) and `is_active` columns.
+fn game_phase_series(game_phases: &[GamePhase]) -> Vec<Series> {
+    let phases: Vec<u8> = game_phases
+        .iter()
+        .map(|game_phase| game_phase.to_column_value())
+        .collect();
+    let is_active: Vec<u8> = game_phases
+        .iter()
+        .map(|game_phase| u8::from(game_phase.is_active()))
+        .collect();
+    vec![
+        UInt8Chunked::new_from_slice("phase", &phases).into_series(),
+        UInt8Chunked::new_from_slice("is_active", &is_active).into_series(),
+    ]
+}
+
+/// Marks frames whose car or ball data was filled in by `GapFillOptions`.
+fn gap_filled_series(is_gap_filled: &[bool]) -> Series {
+    let is_gap_filled: Vec<u8> = is_gap_filled
+        .iter()
+        .map(|is_filled| u8::from(*is_filled))
+        .collect();
+    UInt8Chunked::new_from_slice("is_gap_filled", &is_gap_filled).into_series()
+}
+
+/// Boolean columns are written as UInt8 (0 or 1).
+fn bool_series(name: &str, values: &[Option<bool>]) -> Series {
+    UInt8Chunked::new_from_opt_iter(name, values.iter().map(|value| value.map(u8::from)))
+        .into_series()
 }
 
 #[derive(Debug, Error)]
 pub enum OutputError {
-    #[error("FrameParser missing cleaned_data (check if clean_up method has been called)")]
-    MissingCleanedData,
     #[error("Failed to create DataFrame: {0}")]
     CreateDataFrameError(PolarsError),
+    #[error("Failed to parse replay header: {0}")]
+    HeaderError(HeaderError),
+    #[error("Invalid resample rate: {0}")]
+    InvalidResampleRate(f32),
+    #[error("Cannot resample column {0}: unsupported data type")]
+    UnsupportedResampleColumn(String),
 }
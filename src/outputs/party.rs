@@ -0,0 +1,119 @@
+use crate::actor_handlers::WrappedUniqueId;
+use crate::outputs::Player;
+use serde::Serialize;
+
+/// Players who queued together, grouped by their party leader.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Party {
+    pub leader_id: WrappedUniqueId,
+    pub member_ids: Vec<WrappedUniqueId>,
+}
+
+impl Party {
+    /// Only parties with more than one member in the match are included.
+    pub fn from_players(players: &[Player]) -> Vec<Self> {
+        let mut parties: Vec<Self> = vec![];
+        for player in players.iter() {
+            let leader_id = match &player.party_leader_id {
+                Some(leader_id) => leader_id,
+                None => continue,
+            };
+            match parties
+                .iter_mut()
+                .find(|party| &party.leader_id == leader_id)
+            {
+                Some(party) => party.member_ids.push(player.unique_id.clone()),
+                None => parties.push(Self {
+                    leader_id: leader_id.clone(),
+                    member_ids: vec![player.unique_id.clone()],
+                }),
+            }
+        }
+        parties.retain(|party| party.member_ids.len() > 1);
+        for party in parties.iter_mut() {
+            party
+                .member_ids
+                .sort_by_key(|member_id| member_id.to_string());
+        }
+        parties.sort_by_key(|party| party.leader_id.to_string());
+        parties
+    }
+
+    /// Number of players in the match who queued with the player (including the player).
+    pub fn party_size(parties: &[Self], unique_id: &WrappedUniqueId) -> usize {
+        parties
+            .iter()
+            .find(|party| party.member_ids.contains(unique_id))
+            .map_or(1, |party| party.member_ids.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::outputs::PlayerRole;
+    use boxcars::ActorId;
+
+    fn player(name: &str, party_leader_name: Option<&str>) -> Player {
+        Player {
+            unique_id: WrappedUniqueId::Synthetic(name.to_string(), ActorId(0)),
+            name: name.to_string(),
+            online_id: None,
+            online_id_kind: None,
+            is_bot: false,
+            role: PlayerRole::Player,
+            is_orange: Some(false),
+            party_leader_id: party_leader_name.map(|party_leader_name| {
+                WrappedUniqueId::Synthetic(party_leader_name.to_string(), ActorId(0))
+            }),
+            match_score: 0,
+            match_goals: 0,
+            match_assists: 0,
+            match_saves: 0,
+            match_shots: 0,
+            loadout: None,
+            hitbox: None,
+            camera_settings: None,
+            gap_stats: None,
+        }
+    }
+
+    fn unique_id(name: &str) -> WrappedUniqueId {
+        WrappedUniqueId::Synthetic(name.to_string(), ActorId(0))
+    }
+
+    #[test]
+    fn parties_group_players_by_leader() {
+        let players = vec![
+            player("d", Some("c")),
+            player("a", Some("a")),
+            player("b", Some("a")),
+            player("c", Some("c")),
+            // Solo-queued, either without a leader or as their own party.
+            player("e", None),
+            player("f", Some("f")),
+        ];
+        let parties = Party::from_players(&players);
+        // Members are sorted by unique id, which for synthetic ids is not by name.
+        let mut parties_member_ids: Vec<(WrappedUniqueId, Vec<WrappedUniqueId>)> = parties
+            .iter()
+            .map(|party| {
+                let mut member_ids = party.member_ids.clone();
+                member_ids.sort_by_key(|member_id| format!("{:?}", member_id));
+                (party.leader_id.clone(), member_ids)
+            })
+            .collect();
+        parties_member_ids.sort_by_key(|(leader_id, _)| format!("{:?}", leader_id));
+        assert_eq!(
+            parties_member_ids,
+            vec![
+                (unique_id("a"), vec![unique_id("a"), unique_id("b")]),
+                (unique_id("c"), vec![unique_id("c"), unique_id("d")]),
+            ]
+        );
+
+        assert_eq!(Party::party_size(&parties, &unique_id("b")), 2);
+        assert_eq!(Party::party_size(&parties, &unique_id("e")), 1);
+        assert_eq!(Party::party_size(&parties, &unique_id("f")), 1);
+    }
+}
@@ -1,40 +1,71 @@
-use crate::actor_handlers::{TeamData, WrappedUniqueId};
-use crate::frame_parser::FrameParser;
+use crate::actor_handlers::{
+    is_bot, ActorAttributes, AttributeKey, CameraSettings, WrappedUniqueId,
+};
+use crate::cleaner::GapStats;
+use crate::frame_parser::ParsedReplay;
+use crate::outputs::{CarLoadout, HitboxFamily};
 use boxcars::attributes::RemoteId;
-use boxcars::{ActorId, Attribute};
-use log::error;
+use boxcars::Attribute;
+use log::{error, warn};
 use serde::Serialize;
 use serde::Serializer;
-use std::collections::HashMap;
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+/// Not `Eq`, as `CameraSettings` has float fields.
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct Player {
     #[serde(serialize_with = "serialize_wrapped_unique_id")]
     pub unique_id: WrappedUniqueId,
     pub name: String,
     pub online_id: Option<String>,
     pub online_id_kind: Option<String>,
+    pub is_bot: bool,
+    pub role: PlayerRole,
     pub is_orange: Option<bool>,
+    /// The leader of the party the player queued with (which may be the player).
+    pub party_leader_id: Option<WrappedUniqueId>,
     pub match_score: i32,
     pub match_goals: i32,
     pub match_assists: i32,
     pub match_saves: i32,
     pub match_shots: i32,
+    pub loadout: Option<CarLoadout>,
+    /// Hitbox of the loadout's car body, if known. Bodies missing from `HitboxFamily::from_body` are logged.
+    pub hitbox: Option<HitboxFamily>,
+    pub camera_settings: Option<CameraSettings>,
+    /// Frames missing car data, if the player had a car.
+    pub gap_stats: Option<GapStats>,
 }
 
 impl Player {
-    pub fn from_frame_parser(frame_parser: &FrameParser) -> Vec<Self> {
-        let players_actor = frame_parser.players_actor.borrow();
-        let teams_actor = frame_parser.teams_data.borrow();
-        let players_teams = frame_parser.players_teams.borrow();
-        players_actor
+    pub fn from_parsed_replay(parsed_replay: &ParsedReplay) -> Vec<Self> {
+        parsed_replay
+            .players_actor
             .iter()
             .map(|(wrapped_unique_id, player_actor)| {
                 Player::from(
                     wrapped_unique_id,
                     player_actor,
-                    &teams_actor,
-                    &players_teams,
+                    parsed_replay
+                        .cleaned_data
+                        .players_time_series_team
+                        .get(wrapped_unique_id)
+                        .and_then(|player_teams| get_last_team(player_teams)),
+                    parsed_replay
+                        .players_time_series_player_data
+                        .get(wrapped_unique_id)
+                        .into_iter()
+                        .flat_map(|time_series_player_data| time_series_player_data.team.iter())
+                        // Team actor ids of -1 mean no team.
+                        .any(|team| matches!(team, Some(team_actor_id) if *team_actor_id >= 0)),
+                    parsed_replay
+                        .cleaned_data
+                        .players_time_series_car_data
+                        .get(wrapped_unique_id)
+                        .map(|time_series_car_data| time_series_car_data.stats),
+                    parsed_replay
+                        .players_camera_settings
+                        .get(wrapped_unique_id)
+                        .copied(),
                 )
             })
             .collect()
@@ -42,20 +73,42 @@ impl Player {
 
     pub fn from(
         wrapped_unique_id: &WrappedUniqueId,
-        attributes: &HashMap<String, boxcars::Attribute>,
-        teams_actor: &HashMap<ActorId, TeamData>,
-        players_teams: &HashMap<WrappedUniqueId, HashMap<bool, usize>>,
+        attributes: &ActorAttributes,
+        is_orange: Option<bool>,
+        has_team: bool,
+        gap_stats: Option<GapStats>,
+        camera_settings: Option<CameraSettings>,
     ) -> Self {
+        let loadout = CarLoadout::from(attributes, is_orange.unwrap_or(false));
+        let hitbox = loadout.as_ref().and_then(|loadout| {
+            let hitbox = HitboxFamily::from_body(loadout.body);
+            if hitbox.is_none() {
+                warn!("Unknown hitbox for car body {}", loadout.body);
+            }
+            hitbox
+        });
+        let is_bot = is_bot(attributes);
+        let is_spectator = match attributes.get(AttributeKey::IsSpectator) {
+            Some(Attribute::Boolean(_is_spectator)) => *_is_spectator,
+            _ => false,
+        };
+        let role = if is_bot {
+            PlayerRole::Bot
+        } else if is_spectator || !has_team {
+            PlayerRole::Spectator
+        } else {
+            PlayerRole::Player
+        };
         Self {
             unique_id: wrapped_unique_id.clone(),
-            name: match attributes.get("Engine.PlayerReplicationInfo:PlayerName") {
+            name: match attributes.get(AttributeKey::PlayerName) {
                 Some(Attribute::String(name)) => name.to_string(),
                 _ => {
                     error!("Could not find name for player {:?}", attributes);
                     "".to_string()
                 }
             },
-            online_id: match attributes.get("Engine.PlayerReplicationInfo:UniqueId") {
+            online_id: match attributes.get(AttributeKey::UniqueId) {
                 Some(Attribute::UniqueId(unique_id)) => {
                     Some(match &unique_id.remote_id {
                         RemoteId::PlayStation(id) => id.online_id.to_string(),
@@ -71,7 +124,7 @@ impl Player {
                 }
                 _ => None,
             },
-            online_id_kind: match attributes.get("Engine.PlayerReplicationInfo:UniqueId") {
+            online_id_kind: match attributes.get(AttributeKey::UniqueId) {
                 Some(Attribute::UniqueId(unique_id)) => {
                     Some(match &unique_id.remote_id {
                         RemoteId::PlayStation(_) => "PlayStation".to_string(),
@@ -87,41 +140,47 @@ impl Player {
                 }
                 _ => None,
             },
-            is_orange: match attributes.get("Engine.PlayerReplicationInfo:Team") {
-                Some(Attribute::ActiveActor(team_active_actor)) => {
-                    let team_actor_id = team_active_actor.actor;
-                    if let Some(team_data) = teams_actor.get(&team_actor_id) {
-                        Some(team_data.is_orange)
-                    } else {
-                        try_get_player_team(wrapped_unique_id, players_teams)
-                    }
-                }
-                _ => try_get_player_team(wrapped_unique_id, players_teams),
-            },
-            match_score: match attributes.get("TAGame.PRI_TA:MatchScore") {
+            is_bot,
+            role,
+            is_orange,
+            party_leader_id: WrappedUniqueId::from_party_leader(attributes),
+            match_score: match attributes.get(AttributeKey::MatchScore) {
                 Some(Attribute::Int(match_score)) => *match_score,
                 _ => 0,
             },
-            match_goals: match attributes.get("TAGame.PRI_TA:MatchGoals") {
+            match_goals: match attributes.get(AttributeKey::MatchGoals) {
                 Some(Attribute::Int(match_goals)) => *match_goals,
                 _ => 0,
             },
-            match_assists: match attributes.get("TAGame.PRI_TA:MatchAssists") {
+            match_assists: match attributes.get(AttributeKey::MatchAssists) {
                 Some(Attribute::Int(match_assists)) => *match_assists,
                 _ => 0,
             },
-            match_saves: match attributes.get("TAGame.PRI_TA:MatchSaves") {
+            match_saves: match attributes.get(AttributeKey::MatchSaves) {
                 Some(Attribute::Int(match_saves)) => *match_saves,
                 _ => 0,
             },
-            match_shots: match attributes.get("TAGame.PRI_TA:MatchShots") {
+            match_shots: match attributes.get(AttributeKey::MatchShots) {
                 Some(Attribute::Int(match_shots)) => *match_shots,
                 _ => 0,
             },
+            hitbox,
+            loadout,
+            camera_settings,
+            gap_stats,
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum PlayerRole {
+    Player,
+    Bot,
+    /// PRIs flagged as spectators, or whose team was never replicated during the match.
+    /// Casters join as spectators and cannot be told apart from them.
+    Spectator,
+}
+
 fn serialize_wrapped_unique_id<S>(input: &WrappedUniqueId, serializer: S) -> Result<S::Ok, S::Error>
 where
     S: Serializer,
@@ -129,21 +188,8 @@ where
     serializer.serialize_str(&input.to_string())
 }
 
-/// Tries to get the player's team through FrameParser's running count of whether the team.
-/// (This count is only made once every 500 frames)
-/// This handles cases where players leave the team at the end (and team_actor_id == ActorId(-1))
-/// Only accepts team if at least 3 records were made of the player being in the team, to avoid spectators being recorded as part of the team.
-fn try_get_player_team(
-    wrapped_unique_id: &WrappedUniqueId,
-    players_teams: &HashMap<WrappedUniqueId, HashMap<bool, usize>>,
-) -> Option<bool> {
-    if let Some(player_teams) = players_teams.get(wrapped_unique_id) {
-        player_teams
-            .iter()
-            .max_by(|a, b| a.1.cmp(b.1))
-            .map(|(k, v)| if v > &3 { Some(*k) } else { None })
-            .flatten()
-    } else {
-        None
-    }
+/// The last team the player was on, from their per-frame team.
+/// This handles players leaving the team at the end (where the PRI's team actor is ActorId(-1)).
+fn get_last_team(player_teams: &[Option<bool>]) -> Option<bool> {
+    player_teams.iter().rev().find_map(|is_orange| *is_orange)
 }
@@ -0,0 +1,34 @@
+use crate::actor_handlers::{PowerUp, PowerUpEventData, PowerUpEventKind, WrappedUniqueId};
+use crate::frame_parser::ParsedReplay;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PowerUpEvent {
+    frame_number: i32,
+    time: f32,
+    unique_id: WrappedUniqueId,
+    power_up: PowerUp,
+    kind: PowerUpEventKind,
+    target_unique_id: Option<WrappedUniqueId>,
+}
+
+impl PowerUpEvent {
+    pub fn from_parsed_replay(parsed_replay: &ParsedReplay) -> Vec<Self> {
+        parsed_replay
+            .power_up_events_data
+            .iter()
+            .map(PowerUpEvent::from)
+            .collect()
+    }
+
+    pub fn from(power_up_event_data: &PowerUpEventData) -> Self {
+        Self {
+            frame_number: power_up_event_data.frame_number as i32,
+            time: power_up_event_data.time,
+            unique_id: power_up_event_data.player_wrapped_unique_id.clone(),
+            power_up: power_up_event_data.power_up,
+            kind: power_up_event_data.kind,
+            target_unique_id: power_up_event_data.target_wrapped_unique_id.clone(),
+        }
+    }
+}
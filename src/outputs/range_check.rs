@@ -1,3 +1,4 @@
+use crate::cleaner::{BallType, GameMode};
 use crate::outputs::DataFramesOutput;
 use log::{debug, error};
 use polars::series::Series;
@@ -19,16 +20,11 @@ macro_rules! collection {
     }};
 }
 
-static BALL_RADIUS: f32 = 92.75;
 static BALL_MAX_SPEED: f32 = 6000.0;
 
 static CAR_MAX_SPEED: f32 = 2300.0;
 
-static PITCH_SIDE_WALL: f32 = 4096.0;
-static PITCH_BACK_WALL: f32 = 5120.0;
 static PITCH_FLOOR: f32 = 0.0;
-static PITCH_CEILING: f32 = 2044.0;
-static PITCH_GOAL_DEPTH: f32 = 880.0;
 
 /// Checks the value ranges for the various parsed variables.
 /// Verifies that the ranges are not only within know limits, but also span a reasonable amount of these limits.
@@ -41,22 +37,36 @@ pub struct RangeChecker {
 }
 
 impl RangeChecker {
+    /// Range checks for standard soccar.
     pub fn new() -> Self {
+        Self::for_game_mode(GameMode::Soccar, BallType::Default)
+    }
+
+    /// Range checks for the game mode's arena and ball.
+    pub fn for_game_mode(game_mode: GameMode, ball_type: BallType) -> Self {
+        let arena = game_mode.arena();
+        let pitch_side_wall = arena.side_wall;
+        let pitch_back_wall = arena.back_wall;
+        let pitch_ceiling = arena.ceiling;
+        let pitch_goal_depth = arena.goal_depth;
+        let ball_radius = ball_type.radius();
+        let ball_rest_height = ball_type.rest_height();
+
         let ball = collection! {
             "pos_x".to_string() => Range {
-                min: -PITCH_SIDE_WALL + BALL_RADIUS - 30.0,
-                max: PITCH_SIDE_WALL - BALL_RADIUS + 30.0,
+                min: -pitch_side_wall + ball_radius - 30.0,
+                max: pitch_side_wall - ball_radius + 30.0,
                 buffer: 60.0,
             },
             "pos_y".to_string() => Range {
                 // Higher buffer due to ball being able to enter goal by a lag-dependent amount.
-                min: -(PITCH_BACK_WALL + PITCH_GOAL_DEPTH - BALL_RADIUS) - 30.0,
-                max: PITCH_BACK_WALL + PITCH_GOAL_DEPTH - BALL_RADIUS + 30.0,
-                buffer: PITCH_GOAL_DEPTH + 60.0,
+                min: -(pitch_back_wall + pitch_goal_depth - ball_radius) - 30.0,
+                max: pitch_back_wall + pitch_goal_depth - ball_radius + 30.0,
+                buffer: pitch_goal_depth + 60.0,
             },
             "pos_z".to_string() => Range {
-                min: PITCH_FLOOR + BALL_RADIUS - 30.0,
-                max: PITCH_CEILING - BALL_RADIUS + 30.0,
+                min: PITCH_FLOOR + ball_rest_height - 30.0,
+                max: pitch_ceiling - ball_rest_height + 30.0,
                 buffer: 60.0,
             },
             "vel_x".to_string() => Range {
@@ -76,41 +86,41 @@ impl RangeChecker {
                 buffer: BALL_MAX_SPEED  * 5.0 / 6.0,
             },
             "quat_w".to_string() => Range {
-                min: -1.0 * 1.001,
+                min: -1.001,
                 max: 1.0 * 1.001,
                 buffer: 0.5,
             },
             "quat_x".to_string() => Range {
-                min: -1.0 * 1.001,
+                min: -1.001,
                 max: 1.0 * 1.001,
                 buffer: 0.5,
             },
             "quat_y".to_string() => Range {
-                min: -1.0 * 1.001,
+                min: -1.001,
                 max: 1.0 * 1.001,
                 buffer: 0.5,
             },
             "quat_z".to_string() => Range {
-                min: -1.0 * 1.001,
+                min: -1.001,
                 max: 1.0 * 1.001,
                 buffer: 0.5,
             },
         };
         let player = collection! {
             "pos_x".to_string() => Range {
-                min: -PITCH_SIDE_WALL,
-                max: PITCH_SIDE_WALL,
-                buffer: PITCH_SIDE_WALL / 5.0,
+                min: -pitch_side_wall,
+                max: pitch_side_wall,
+                buffer: pitch_side_wall / 5.0,
             },
             "pos_y".to_string() => Range {
-                min: -(PITCH_BACK_WALL + PITCH_GOAL_DEPTH),
-                max: PITCH_BACK_WALL + PITCH_GOAL_DEPTH,
-                buffer: PITCH_BACK_WALL / 5.0,
+                min: -(pitch_back_wall + pitch_goal_depth),
+                max: pitch_back_wall + pitch_goal_depth,
+                buffer: pitch_back_wall / 5.0,
             },
             "pos_z".to_string() => Range {
                 min: PITCH_FLOOR,
-                max: PITCH_CEILING ,
-                buffer: PITCH_CEILING / 2.0,
+                max: pitch_ceiling ,
+                buffer: pitch_ceiling / 2.0,
             },
             "vel_x".to_string() => Range {
                 min: -CAR_MAX_SPEED,
@@ -128,22 +138,22 @@ impl RangeChecker {
                 buffer: CAR_MAX_SPEED / 2.0,
             },
             "quat_w".to_string() => Range {
-                min: -1.0 * 1.001,
+                min: -1.001,
                 max: 1.0 * 1.001,
                 buffer: 0.5,
             },
             "quat_x".to_string() => Range {
-                min: -1.0 * 1.001,
+                min: -1.001,
                 max: 1.0 * 1.001,
                 buffer: 0.5,
             },
             "quat_y".to_string() => Range {
-                min: -1.0 * 1.001,
+                min: -1.001,
                 max: 1.0 * 1.001,
                 buffer: 0.5,
             },
             "quat_z".to_string() => Range {
-                min: -1.0 * 1.001,
+                min: -1.001,
                 max: 1.0 * 1.001,
                 buffer: 0.5,
             },
@@ -253,8 +263,8 @@ mod tests {
         let file_path = PathBuf::from("assets\\replays\\rlcs-season-5-final.replay");
         // let file_path = PathBuf::from("assets\\replays\\soccar-lan.replay");
         let carball_parser = CarballParser::parse_file(file_path, false).expect("failed to parse");
-        dbg!(&carball_parser.frame_parser.replay_version);
-        let data_frames = DataFramesOutput::generate_from(&carball_parser.frame_parser).unwrap();
+        dbg!(&carball_parser.parsed_replay.replay_version);
+        let data_frames = DataFramesOutput::generate_from(&carball_parser.parsed_replay).unwrap();
 
         let range_checker = RangeChecker::new();
         assert!(range_checker.check_ranges(&data_frames).unwrap());
@@ -0,0 +1,454 @@
+use crate::outputs::{DataFramesOutput, OutputError};
+use polars::prelude::{
+    DataFrame, DataType, Float32Chunked, Int32Chunked, IntoSeries, NewChunkedArray, Series,
+    UInt8Chunked,
+};
+use std::collections::HashMap;
+
+static QUATERNION_COLUMNS: [&str; 4] = ["quat_w", "quat_x", "quat_y", "quat_z"];
+static POSITION_COLUMNS: [&str; 3] = ["pos_x", "pos_y", "pos_z"];
+/// Frame-to-frame movement beyond which a car or ball is taken to have been teleported (e.g. respawned).
+static MAX_INTERPOLATED_DISTANCE: f32 = 1000.0;
+
+/// A resampled row, taken from between frames `frame_number` and `frame_number + 1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Sample {
+    time: f32,
+    frame_number: usize,
+    /// Fraction of the way from `frame_number` to the next frame.
+    fraction: f32,
+}
+
+impl Sample {
+    fn is_interpolated(&self) -> bool {
+        self.fraction > f32::EPSILON
+    }
+
+    fn nearest_frame_number(&self) -> usize {
+        if self.fraction < 0.5 {
+            self.frame_number
+        } else {
+            self.frame_number + 1
+        }
+    }
+}
+
+impl DataFramesOutput {
+    /// Resamples the game, ball, player and boost pad DataFrames onto a fixed rate (in Hz), using the game's `time` column.
+    /// Float columns are linearly interpolated (with quaternions slerped), while other columns take the value of the nearest frame.
+    /// Rows between frames where `is_sleeping` changes or the position jumps (respawns, demolitions) are not interpolated either.
+    /// An `is_interpolated` column marks rows with interpolated values.
+    pub fn resample(&self, rate: f32) -> Result<Self, OutputError> {
+        if !rate.is_finite() || rate <= 0.0 {
+            return Err(OutputError::InvalidResampleRate(rate));
+        }
+        let times: Vec<Option<f32>> = self
+            .game
+            .column("time")
+            .map_err(OutputError::CreateDataFrameError)?
+            .f32()
+            .map_err(OutputError::CreateDataFrameError)?
+            .into_iter()
+            .collect();
+        let times: Vec<f32> = times.into_iter().map(|time| time.unwrap_or(0.0)).collect();
+        let samples = get_samples(&times, rate);
+
+        let mut game = resample_df(&self.game, &samples)?;
+        let resampled_times: Vec<f32> = samples.iter().map(|sample| sample.time).collect();
+        game.replace(
+            "time",
+            Float32Chunked::new_from_slice("time", &resampled_times).into_series(),
+        )
+        .map_err(OutputError::CreateDataFrameError)?;
+        game.replace(
+            "delta",
+            Float32Chunked::new_from_slice("delta", &vec![1.0 / rate; samples.len()]).into_series(),
+        )
+        .map_err(OutputError::CreateDataFrameError)?;
+
+        let mut players = HashMap::new();
+        for (wrapped_unique_id, player_df) in self.players.iter() {
+            players.insert(wrapped_unique_id.clone(), resample_df(player_df, &samples)?);
+        }
+
+        Ok(Self {
+            game,
+            ball: resample_df(&self.ball, &samples)?,
+            players,
+            boost_pads: resample_df(&self.boost_pads, &samples)?,
+        })
+    }
+}
+
+fn get_samples(times: &[f32], rate: f32) -> Vec<Sample> {
+    let mut samples = vec![];
+    let (first_time, last_time) = match (times.first(), times.last()) {
+        (Some(first_time), Some(last_time)) => (*first_time, *last_time),
+        _ => return samples,
+    };
+
+    let mut frame_number = 0;
+    let mut sample_number = 0;
+    loop {
+        let time = first_time + sample_number as f32 / rate;
+        if time > last_time {
+            break;
+        }
+        while frame_number + 1 < times.len() && times[frame_number + 1] <= time {
+            frame_number += 1;
+        }
+        let fraction = if frame_number + 1 < times.len() {
+            let frame_duration = times[frame_number + 1] - times[frame_number];
+            if frame_duration > 0.0 {
+                ((time - times[frame_number]) / frame_duration).clamp(0.0, 1.0)
+            } else {
+                0.0
+            }
+        } else {
+            0.0
+        };
+        samples.push(Sample {
+            time,
+            frame_number,
+            fraction,
+        });
+        sample_number += 1;
+    }
+    samples
+}
+
+fn resample_df(df: &DataFrame, samples: &[Sample]) -> Result<DataFrame, OutputError> {
+    let discontinuities = get_discontinuities(df)?;
+    let samples: Vec<Sample> = samples
+        .iter()
+        .map(|sample| {
+            if sample.is_interpolated() && discontinuities[sample.frame_number] {
+                Sample {
+                    frame_number: sample.nearest_frame_number(),
+                    fraction: 0.0,
+                    ..*sample
+                }
+            } else {
+                *sample
+            }
+        })
+        .collect();
+    let samples = &samples;
+
+    let mut columns = vec![];
+    for series in df.get_columns() {
+        if QUATERNION_COLUMNS.contains(&series.name()) {
+            continue;
+        }
+        columns.push(resample_series(series, samples)?);
+    }
+    if QUATERNION_COLUMNS
+        .iter()
+        .all(|column_name| df.column(column_name).is_ok())
+    {
+        columns.extend(resample_quaternion_series(df, samples)?);
+    }
+    let is_interpolated: Vec<u8> = samples
+        .iter()
+        .map(|sample| u8::from(sample.is_interpolated()))
+        .collect();
+    columns.push(UInt8Chunked::new_from_slice("is_interpolated", &is_interpolated).into_series());
+    DataFrame::new(columns).map_err(OutputError::CreateDataFrameError)
+}
+
+/// Whether values should not be interpolated between each frame and the next.
+fn get_discontinuities(df: &DataFrame) -> Result<Vec<bool>, OutputError> {
+    let mut discontinuities = vec![false; df.height()];
+    if let Ok(is_sleeping) = df.column("is_sleeping") {
+        let is_sleeping: Vec<Option<u8>> = is_sleeping
+            .u8()
+            .map_err(OutputError::CreateDataFrameError)?
+            .into_iter()
+            .collect();
+        for (discontinuity, window) in discontinuities.iter_mut().zip(is_sleeping.windows(2)) {
+            *discontinuity |= window[0] != window[1];
+        }
+    }
+    if POSITION_COLUMNS
+        .iter()
+        .all(|column_name| df.column(column_name).is_ok())
+    {
+        let mut position_values: Vec<Vec<Option<f32>>> = vec![];
+        for column_name in POSITION_COLUMNS.iter() {
+            position_values.push(
+                df.column(column_name)
+                    .map_err(OutputError::CreateDataFrameError)?
+                    .f32()
+                    .map_err(OutputError::CreateDataFrameError)?
+                    .into_iter()
+                    .collect(),
+            );
+        }
+        for (frame_number, discontinuity) in discontinuities.iter_mut().enumerate() {
+            if frame_number + 1 >= df.height() {
+                break;
+            }
+            let mut distance_squared = 0.0;
+            for values in position_values.iter() {
+                if let (Some(start), Some(end)) = (values[frame_number], values[frame_number + 1]) {
+                    distance_squared += (end - start) * (end - start);
+                }
+            }
+            *discontinuity |=
+                distance_squared > MAX_INTERPOLATED_DISTANCE * MAX_INTERPOLATED_DISTANCE;
+        }
+    }
+    Ok(discontinuities)
+}
+
+fn resample_series(series: &Series, samples: &[Sample]) -> Result<Series, OutputError> {
+    let name = series.name();
+    match series.dtype() {
+        DataType::Float32 => {
+            let values: Vec<Option<f32>> = series
+                .f32()
+                .map_err(OutputError::CreateDataFrameError)?
+                .into_iter()
+                .collect();
+            let resampled_values: Vec<Option<f32>> = samples
+                .iter()
+                .map(|sample| interpolate(&values, sample))
+                .collect();
+            Ok(Float32Chunked::new_from_opt_slice(name, &resampled_values).into_series())
+        }
+        DataType::Int32 => {
+            let values: Vec<Option<i32>> = series
+                .i32()
+                .map_err(OutputError::CreateDataFrameError)?
+                .into_iter()
+                .collect();
+            let resampled_values: Vec<Option<i32>> = samples
+                .iter()
+                .map(|sample| values[sample.nearest_frame_number()])
+                .collect();
+            Ok(Int32Chunked::new_from_opt_slice(name, &resampled_values).into_series())
+        }
+        DataType::UInt8 => {
+            let values: Vec<Option<u8>> = series
+                .u8()
+                .map_err(OutputError::CreateDataFrameError)?
+                .into_iter()
+                .collect();
+            let resampled_values: Vec<Option<u8>> = samples
+                .iter()
+                .map(|sample| values[sample.nearest_frame_number()])
+                .collect();
+            Ok(UInt8Chunked::new_from_opt_slice(name, &resampled_values).into_series())
+        }
+        _ => Err(OutputError::UnsupportedResampleColumn(name.to_string())),
+    }
+}
+
+/// Linearly interpolates between the sample's frames, falling back to the nearest frame's value if either frame is missing data.
+fn interpolate(values: &[Option<f32>], sample: &Sample) -> Option<f32> {
+    if !sample.is_interpolated() {
+        return values[sample.frame_number];
+    }
+    match (values[sample.frame_number], values[sample.frame_number + 1]) {
+        (Some(start), Some(end)) => Some(start + (end - start) * sample.fraction),
+        _ => values[sample.nearest_frame_number()],
+    }
+}
+
+fn resample_quaternion_series(
+    df: &DataFrame,
+    samples: &[Sample],
+) -> Result<Vec<Series>, OutputError> {
+    let mut quaternion_values: Vec<Vec<Option<f32>>> = vec![];
+    for column_name in QUATERNION_COLUMNS.iter() {
+        quaternion_values.push(
+            df.column(column_name)
+                .map_err(OutputError::CreateDataFrameError)?
+                .f32()
+                .map_err(OutputError::CreateDataFrameError)?
+                .into_iter()
+                .collect(),
+        );
+    }
+    let get_quaternion = |frame_number: usize| -> Option<[f32; 4]> {
+        Some([
+            quaternion_values[0][frame_number]?,
+            quaternion_values[1][frame_number]?,
+            quaternion_values[2][frame_number]?,
+            quaternion_values[3][frame_number]?,
+        ])
+    };
+
+    let mut resampled_values: Vec<Vec<Option<f32>>> = vec![vec![]; 4];
+    for sample in samples {
+        let quaternion = if !sample.is_interpolated() {
+            get_quaternion(sample.frame_number)
+        } else {
+            match (
+                get_quaternion(sample.frame_number),
+                get_quaternion(sample.frame_number + 1),
+            ) {
+                (Some(start), Some(end)) => Some(slerp(start, end, sample.fraction)),
+                _ => get_quaternion(sample.nearest_frame_number()),
+            }
+        };
+        for (index, values) in resampled_values.iter_mut().enumerate() {
+            values.push(quaternion.map(|quaternion| quaternion[index]));
+        }
+    }
+
+    Ok(QUATERNION_COLUMNS
+        .iter()
+        .zip(resampled_values.iter())
+        .map(|(column_name, values)| {
+            Float32Chunked::new_from_opt_slice(column_name, values).into_series()
+        })
+        .collect())
+}
+
+/// Spherical linear interpolation between quaternions (w, x, y, z).
+fn slerp(start: [f32; 4], end: [f32; 4], fraction: f32) -> [f32; 4] {
+    let mut end = end;
+    let mut dot: f32 = start.iter().zip(end.iter()).map(|(a, b)| a * b).sum();
+    // Take the shorter path.
+    if dot < 0.0 {
+        end = [-end[0], -end[1], -end[2], -end[3]];
+        dot = -dot;
+    }
+
+    let (start_weight, end_weight) = if dot > 0.9995 {
+        // Quaternions are nearly parallel: fall back to linear interpolation (normalised below).
+        (1.0 - fraction, fraction)
+    } else {
+        let theta = dot.acos();
+        let sin_theta = theta.sin();
+        (
+            ((1.0 - fraction) * theta).sin() / sin_theta,
+            (fraction * theta).sin() / sin_theta,
+        )
+    };
+
+    let mut result = [0.0; 4];
+    for (value, (start_value, end_value)) in result.iter_mut().zip(start.iter().zip(end.iter())) {
+        *value = start_weight * start_value + end_weight * end_value;
+    }
+    let norm = result.iter().map(|value| value * value).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        for value in result.iter_mut() {
+            *value /= norm;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_quaternion_close(actual: [f32; 4], expected: [f32; 4]) {
+        for (actual_value, expected_value) in actual.iter().zip(expected.iter()) {
+            assert!(
+                (actual_value - expected_value).abs() < 1e-5,
+                "{:?} != {:?}",
+                actual,
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn samples_fall_between_frames() {
+        let samples = get_samples(&[0.0, 0.1, 0.3], 10.0);
+        let sample_times: Vec<f32> = samples.iter().map(|sample| sample.time).collect();
+        assert_eq!(sample_times, vec![0.0, 0.1, 0.2, 0.3]);
+        assert_eq!(samples[0].frame_number, 0);
+        assert!(!samples[0].is_interpolated());
+        assert_eq!(samples[1].frame_number, 1);
+        assert!(!samples[1].is_interpolated());
+        assert_eq!(samples[2].frame_number, 1);
+        assert!((samples[2].fraction - 0.5).abs() < 1e-5);
+        assert_eq!(samples[3].frame_number, 2);
+    }
+
+    #[test]
+    fn samples_empty_without_frames() {
+        assert!(get_samples(&[], 30.0).is_empty());
+    }
+
+    #[test]
+    fn slerp_endpoints_and_midpoint() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        // 90 degrees about z.
+        let half_angle = std::f32::consts::FRAC_PI_4;
+        let rotated = [half_angle.cos(), 0.0, 0.0, half_angle.sin()];
+        assert_quaternion_close(slerp(identity, rotated, 0.0), identity);
+        assert_quaternion_close(slerp(identity, rotated, 1.0), rotated);
+        // 45 degrees about z.
+        let quarter_angle = std::f32::consts::FRAC_PI_8;
+        assert_quaternion_close(
+            slerp(identity, rotated, 0.5),
+            [quarter_angle.cos(), 0.0, 0.0, quarter_angle.sin()],
+        );
+    }
+
+    #[test]
+    fn slerp_takes_shorter_path() {
+        let identity = [1.0, 0.0, 0.0, 0.0];
+        let negated_identity = [-1.0, 0.0, 0.0, 0.0];
+        assert_quaternion_close(slerp(identity, negated_identity, 0.5), identity);
+    }
+
+    #[test]
+    fn interpolation_skips_respawns() {
+        let df = DataFrame::new(vec![
+            Float32Chunked::new_from_slice("pos_x", &[0.0, 100.0, 4000.0]).into_series(),
+            Float32Chunked::new_from_slice("pos_y", &[0.0, 0.0, 0.0]).into_series(),
+            Float32Chunked::new_from_slice("pos_z", &[17.0, 17.0, 17.0]).into_series(),
+        ])
+        .unwrap();
+        let samples = get_samples(&[0.0, 0.1, 0.2], 40.0);
+        let resampled_df = resample_df(&df, &samples).unwrap();
+        let pos_x: Vec<Option<f32>> = resampled_df
+            .column("pos_x")
+            .unwrap()
+            .f32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        let is_interpolated: Vec<Option<u8>> = resampled_df
+            .column("is_interpolated")
+            .unwrap()
+            .u8()
+            .unwrap()
+            .into_iter()
+            .collect();
+        // Interpolated before the jump, then taken from the nearest frame.
+        assert!((pos_x[1].unwrap() - 25.0).abs() < 1e-3);
+        assert_eq!(is_interpolated[1], Some(1));
+        assert_eq!(pos_x[5], Some(100.0));
+        assert_eq!(pos_x[6], Some(4000.0));
+        assert_eq!(is_interpolated[5], Some(0));
+    }
+
+    #[test]
+    fn interpolation_skips_sleeping_changes() {
+        let df = DataFrame::new(vec![
+            Float32Chunked::new_from_slice("vel_x", &[0.0, 100.0]).into_series(),
+            UInt8Chunked::new_from_slice("is_sleeping", &[1, 0]).into_series(),
+        ])
+        .unwrap();
+        let samples = get_samples(&[0.0, 0.1], 40.0);
+        let resampled_df = resample_df(&df, &samples).unwrap();
+        let vel_x: Vec<Option<f32>> = resampled_df
+            .column("vel_x")
+            .unwrap()
+            .f32()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(
+            vel_x,
+            vec![Some(0.0), Some(0.0), Some(100.0), Some(100.0), Some(100.0)]
+        );
+    }
+}
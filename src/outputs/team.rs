@@ -1,23 +1,33 @@
 use crate::actor_handlers::TeamData;
-use crate::frame_parser::FrameParser;
+use crate::frame_parser::ParsedReplay;
 use serde::Serialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Team {
     score: i32,
     is_orange: bool,
+    name: Option<String>,
+    club_id: Option<u64>,
+    primary_color: Option<u8>,
+    accent_color: Option<u8>,
 }
 
 impl Team {
-    pub fn from_frame_parser(frame_parser: &FrameParser) -> Vec<Self> {
-        let teams_data = frame_parser.teams_data.borrow();
-        teams_data.values().map(Team::from).collect()
+    /// Teams are ordered blue then orange.
+    pub fn from_parsed_replay(parsed_replay: &ParsedReplay) -> Vec<Self> {
+        let mut teams: Vec<Self> = parsed_replay.teams_data.values().map(Team::from).collect();
+        teams.sort_by_key(|team| team.is_orange);
+        teams
     }
 
     pub fn from(data: &TeamData) -> Self {
         Self {
             score: data.score,
             is_orange: data.is_orange,
+            name: data.name.clone(),
+            club_id: data.club_id,
+            primary_color: data.primary_color,
+            accent_color: data.accent_color,
         }
     }
 }
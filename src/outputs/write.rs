@@ -1,4 +1,4 @@
-use crate::outputs::{DataFramesOutput, MetadataOutput};
+use crate::outputs::{DataFramesOutput, HeaderOutput, MetadataOutput};
 use clap::arg_enum;
 use log::info;
 use polars::error::PolarsError;
@@ -71,14 +71,37 @@ impl ParseOutputWriter {
                 data_frame_output_format,
             )?;
 
+            let mut boost_pads_data_frame_path = self.output_dir.clone();
+            boost_pads_data_frame_path.push("__boost_pads");
+            write_df(
+                boost_pads_data_frame_path,
+                &_data_frames_output.boost_pads,
+                data_frame_output_format,
+            )?;
+
             for (wrapped_unique_id, player_df) in _data_frames_output.players.iter() {
                 let mut player_data_frame_path = self.output_dir.clone();
-                player_data_frame_path.push(format!("player_{}", wrapped_unique_id.to_string()));
+                player_data_frame_path.push(format!("player_{}", wrapped_unique_id));
                 write_df(player_data_frame_path, player_df, data_frame_output_format)?;
             }
         }
         Ok(())
     }
+
+    pub fn write_header_output(
+        &self,
+        header_output: &HeaderOutput,
+    ) -> Result<(), ParseOutputWriterError> {
+        let mut header_output_path = self.output_dir.clone();
+        header_output_path.push("header.json");
+        serde_json::to_writer_pretty(
+            &File::create(header_output_path)
+                .map_err(ParseOutputWriterError::CreateMetadataFileError)?,
+            header_output,
+        )
+        .map_err(ParseOutputWriterError::WriteMetadataJsonError)?;
+        Ok(())
+    }
 }
 
 pub fn write_df(
@@ -96,7 +119,8 @@ pub fn write_df_to_csv<P: AsRef<Path> + Debug>(
     path: P,
     df: &DataFrame,
 ) -> Result<(), ParseOutputWriterError> {
-    let mut csv_file = File::create(&path).expect("Could not create CSV file.");
+    let mut csv_file =
+        File::create(&path).map_err(ParseOutputWriterError::CreateDataFrameFileError)?;
     CsvWriter::new(&mut csv_file)
         .has_headers(true)
         .with_delimiter(b',')
@@ -110,7 +134,7 @@ pub fn write_df_to_parquet<P: AsRef<Path> + Debug>(
     path: P,
     df: &DataFrame,
 ) -> Result<(), ParseOutputWriterError> {
-    let file = File::create(&path).expect("Could not create parquet file");
+    let file = File::create(&path).map_err(ParseOutputWriterError::CreateDataFrameFileError)?;
     ParquetWriter::new(file)
         .finish(df)
         .map_err(ParseOutputWriterError::WriteDataFrameError)?;
@@ -124,6 +148,8 @@ pub enum ParseOutputWriterError {
     CreateMetadataFileError(std::io::Error),
     #[error("Failed to write metadata file to JSON: {0}")]
     WriteMetadataJsonError(serde_json::Error),
+    #[error("Failed to create DataFrame file: {0}")]
+    CreateDataFrameFileError(std::io::Error),
     #[error("Failed to write DataFrame: {0}")]
     WriteDataFrameError(PolarsError),
     #[error("DataFrame output format not set")]
@@ -0,0 +1,59 @@
+/// Defines a time-series row struct alongside a columnar buffer for it.
+/// The buffer holds one preallocated `Vec` per field, indexed by frame number, with `is_set` marking frames that have data.
+/// Columns can then be turned into polars Series without first collecting rows.
+macro_rules! time_series_columns {
+    (
+        $(#[$meta:meta])*
+        pub struct $data:ident => $columns:ident {
+            $($(#[$field_meta:meta])* pub $field:ident: $ty:ty,)*
+        }
+    ) => {
+        $(#[$meta])*
+        pub struct $data {
+            $($(#[$field_meta])* pub $field: $ty,)*
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct $columns {
+            pub is_set: Vec<bool>,
+            $($(#[$field_meta])* pub $field: Vec<$ty>,)*
+        }
+
+        impl $columns {
+            pub fn new(frame_count: usize) -> Self {
+                Self {
+                    is_set: vec![false; frame_count],
+                    $($field: vec![Default::default(); frame_count],)*
+                }
+            }
+
+            pub fn len(&self) -> usize {
+                self.is_set.len()
+            }
+
+            pub fn is_empty(&self) -> bool {
+                self.is_set.is_empty()
+            }
+
+            pub fn insert(&mut self, frame_number: usize, data: $data) {
+                self.is_set[frame_number] = true;
+                $(self.$field[frame_number] = data.$field;)*
+            }
+
+            pub fn get(&self, frame_number: usize) -> Option<$data> {
+                if *self.is_set.get(frame_number)? {
+                    Some($data {
+                        $($field: self.$field[frame_number],)*
+                    })
+                } else {
+                    None
+                }
+            }
+
+            pub fn iter(&self) -> impl Iterator<Item = (usize, $data)> + '_ {
+                (0..self.len())
+                    .filter_map(move |frame_number| Some((frame_number, self.get(frame_number)?)))
+            }
+        }
+    };
+}